@@ -0,0 +1,324 @@
+use circus::{Instruction, State};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const SIZES: [usize; 3] = [32, 256, 1024];
+
+/// Number of gates applied per iteration.
+const GATES: usize = 1000;
+
+/// A circuit of `len` uniformly random H, S and CX gates on `n` qubits.
+fn random_circuit(n: usize, len: usize, rng: &mut StdRng) -> Vec<Instruction> {
+    (0..len)
+        .map(|_| {
+            let a = rng.gen_range(0..n);
+            match rng.gen_range(0..3) {
+                0 => Instruction::h(a),
+                1 => Instruction::p(a),
+                _ => {
+                    let b = (a + rng.gen_range(1..n)) % n;
+                    Instruction::cx(a, b)
+                }
+            }
+        })
+        .collect()
+}
+
+/// A state on `n` qubits scrambled by a random circuit, so rows aren't mostly zero.
+fn scrambled(n: usize, rng: &mut StdRng) -> State {
+    let mut state = State::new(n);
+    state.run(random_circuit(n, 4 * n, rng)).for_each(drop);
+    state
+}
+
+fn gates(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gates");
+    group.throughput(Throughput::Elements(GATES as u64));
+
+    for n in SIZES {
+        let mut rng = StdRng::seed_from_u64(n as u64);
+        let mut state = scrambled(n, &mut rng);
+        let targets: Vec<_> = (0..GATES)
+            .map(|_| {
+                let a = rng.gen_range(0..n);
+                (a, (a + rng.gen_range(1..n)) % n)
+            })
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("h", n), &targets, |b, targets| {
+            b.iter(|| targets.iter().for_each(|&(a, _)| state.h(a)))
+        });
+        group.bench_with_input(BenchmarkId::new("s", n), &targets, |b, targets| {
+            b.iter(|| targets.iter().for_each(|&(a, _)| state.s(a)))
+        });
+        group.bench_with_input(BenchmarkId::new("cx", n), &targets, |b, targets| {
+            b.iter(|| targets.iter().for_each(|&(a, t)| state.cx(a, t)))
+        });
+        group.bench_with_input(BenchmarkId::new("circuit", n), &n, |b, &n| {
+            let circuit = random_circuit(n, GATES, &mut rng);
+            b.iter(|| state.run(circuit.iter().cloned()).for_each(drop))
+        });
+    }
+    group.finish();
+}
+
+fn layers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("layers");
+    let n = 1000;
+    let mut rng = StdRng::seed_from_u64(n as u64);
+
+    // 20 layers of n random H, S and CX gates, then measuring every qubit, all on one tableau buffer
+    let circuit = random_circuit(n, 20 * n, &mut rng);
+    group.bench_with_input(BenchmarkId::new("gates", n), &circuit, |b, circuit| {
+        b.iter_batched_ref(
+            || State::new(n),
+            |state| state.run(circuit.iter().cloned()).for_each(drop),
+            BatchSize::LargeInput,
+        )
+    });
+
+    let mut state = State::new(n);
+    state.run(circuit).for_each(drop);
+    group.bench_with_input(BenchmarkId::new("measure_all", n), &state, |b, state| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| (0..n).map(|q| state.measure(q)).collect::<Vec<_>>(),
+            BatchSize::LargeInput,
+        )
+    });
+
+    // A layer of Hadamards on every qubit, as one pass over the tableau or one per qubit
+    group.bench_with_input(BenchmarkId::new("h_all_qubits", n), &state, |b, state| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| state.h_all_qubits(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_with_input(BenchmarkId::new("h_loop", n), &state, |b, state| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| (0..n).for_each(|q| state.h(q)),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn deep(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deep");
+    let n = 200;
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    let state = scrambled(n, &mut rng);
+
+    // Measurements after each layer of gates are what multiply rows and compute their phases
+    let mut circuit = Vec::new();
+    for _ in 0..50 {
+        circuit.extend(random_circuit(n, n, &mut rng));
+        circuit.extend((0..10).map(|_| Instruction::measure(rng.gen_range(0..n))));
+    }
+
+    group.throughput(Throughput::Elements(circuit.len() as u64));
+    group.bench_with_input(BenchmarkId::new("circuit", n), &circuit, |b, circuit| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| state.run(circuit.iter().cloned()).for_each(drop),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn rows(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rows");
+    let n = 2048;
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    let state = scrambled(n, &mut rng);
+
+    // Each random measurement multiplies up to 2n rows of 128 words, which is where the simd feature applies
+    let targets: Vec<_> = (0..64).map(|_| rng.gen_range(0..n)).collect();
+    group.throughput(Throughput::Elements(targets.len() as u64));
+    group.bench_with_input(BenchmarkId::new("measure", n), &state, |b, state| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| {
+                targets
+                    .iter()
+                    .map(|&q| state.measure(q))
+                    .collect::<Vec<_>>()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+/// One round of syndrome extraction for a distance-`d` rotated surface code patch,
+/// with data qubit `(i, j)` at `i * d + j` followed by an ancilla for each stabilizer.
+///
+/// Returns the number of qubits, the gates and the ancillas to measure.
+fn surface_code_round(d: usize) -> (usize, Vec<Instruction>, Vec<usize>) {
+    let mut gates = Vec::new();
+    let mut ancillas = Vec::new();
+    let mut next = d * d;
+
+    // A plaquette at each corner (i, j) of the grid touches the data qubits around it,
+    // with weight-two X plaquettes only on the top and bottom and Z only on the left and right.
+    // For odd `d` this also leaves out the corners.
+    for i in 0..=d {
+        for j in 0..=d {
+            let is_x = (i + j) % 2 == 0;
+            let top_or_bottom = i == 0 || i == d;
+            let left_or_right = j == 0 || j == d;
+            if (top_or_bottom && !is_x) || (left_or_right && is_x) {
+                continue;
+            }
+
+            let ancilla = next;
+            next += 1;
+            ancillas.push(ancilla);
+
+            let data = [(i, j), (i, j + 1), (i + 1, j), (i + 1, j + 1)]
+                .into_iter()
+                .filter(|&(a, b)| (1..=d).contains(&a) && (1..=d).contains(&b))
+                .map(|(a, b)| (a - 1) * d + b - 1);
+            if is_x {
+                gates.push(Instruction::h(ancilla));
+                gates.extend(data.map(|q| Instruction::cx(ancilla, q)));
+                gates.push(Instruction::h(ancilla));
+            } else {
+                gates.extend(data.map(|q| Instruction::cx(q, ancilla)));
+            }
+        }
+    }
+
+    (next, gates, ancillas)
+}
+
+fn surface(c: &mut Criterion) {
+    let mut group = c.benchmark_group("surface");
+
+    for d in [9, 15, 25] {
+        let (n, round, ancillas) = surface_code_round(d);
+
+        // The first round projects into the code space, after which every syndrome is deterministic
+        let mut state = State::new(n);
+        state.run(round.iter().cloned()).for_each(drop);
+        ancillas.iter().for_each(|&q| {
+            state.measure(q);
+        });
+        state.run(round).for_each(drop);
+        assert!(state
+            .clone()
+            .measure_commuting(&ancillas)
+            .iter()
+            .all(|m| !m.is_random()));
+
+        group.throughput(Throughput::Elements(ancillas.len() as u64));
+        group.bench_with_input(BenchmarkId::new("measure", d), &state, |b, state| {
+            b.iter_batched_ref(
+                || state.clone(),
+                |state| {
+                    ancillas
+                        .iter()
+                        .map(|&q| state.measure(q))
+                        .collect::<Vec<_>>()
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("commuting", d), &state, |b, state| {
+            b.iter_batched_ref(
+                || state.clone(),
+                |state| state.measure_commuting(&ancillas),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn ghz(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ghz");
+    let n = 500;
+    let targets: Vec<_> = (1..n).collect();
+
+    group.bench_function(BenchmarkId::new("cx_fanout", n), |b| {
+        b.iter_batched_ref(
+            || State::new(n),
+            |state| {
+                state.h(0);
+                state.cx_fanout(0, &targets);
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function(BenchmarkId::new("cx_loop", n), |b| {
+        b.iter_batched_ref(
+            || State::new(n),
+            |state| {
+                state.h(0);
+                targets.iter().for_each(|&t| state.cx(0, t));
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn measure(c: &mut Criterion) {
+    let mut group = c.benchmark_group("measure");
+
+    for n in SIZES {
+        let mut rng = StdRng::seed_from_u64(n as u64);
+        let state = scrambled(n, &mut rng);
+        let targets: Vec<_> = (0..n).collect();
+
+        // Measuring every qubit of a fresh copy mixes random and determined outcomes
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("all", n), &state, |b, state| {
+            b.iter_batched_ref(
+                || state.clone(),
+                |state| (0..n).map(|q| state.measure(q)).collect::<Vec<_>>(),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("commuting", n), &state, |b, state| {
+            b.iter_batched_ref(
+                || state.clone(),
+                |state| state.measure_commuting(&targets),
+                BatchSize::LargeInput,
+            )
+        });
+
+        // After one pass every outcome is determined, which is where sharing the elimination pays off
+        let mut measured = state.clone();
+        measured.measure_all();
+        group.bench_with_input(
+            BenchmarkId::new("all_deterministic", n),
+            &measured,
+            |b, state| {
+                b.iter_batched_ref(
+                    || state.clone(),
+                    |state| (0..n).map(|q| state.measure(q)).collect::<Vec<_>>(),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("commuting_deterministic", n),
+            &measured,
+            |b, state| {
+                b.iter_batched_ref(
+                    || state.clone(),
+                    |state| state.measure_commuting(&targets),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, gates, layers, deep, rows, surface, ghz, measure);
+criterion_main!(benches);
@@ -0,0 +1,23 @@
+use std::{env, process::Command};
+
+/// Set `circus_nightly` when building with a nightly compiler,
+/// so the `simd` feature only enables portable SIMD where it is available.
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(circus_nightly)");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let is_nightly = Command::new(rustc)
+        .arg("-vV")
+        .output()
+        .map(|output| {
+            let version = String::from_utf8_lossy(&output.stdout);
+            version.contains("-nightly") || version.contains("-dev")
+        })
+        .unwrap_or(false);
+
+    if is_nightly {
+        println!("cargo:rustc-cfg=circus_nightly");
+    }
+}
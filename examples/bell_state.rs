@@ -8,5 +8,5 @@ fn main() {
     state.measure(1);
 
     println!("{state}");
-    println!("{}", state.ket());
+    println!("{}", state.ket().unwrap());
 }
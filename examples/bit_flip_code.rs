@@ -0,0 +1,42 @@
+use circus::State;
+
+fn main() {
+    // Try every single bit flip, along with no error at all
+    for error in [None, Some(0), Some(1), Some(2)] {
+        // Encode a logical |1> into data qubits 0, 1 and 2, with qubits 3 and 4 as syndrome ancillas
+        let mut state = State::new(5);
+        state.x(0);
+        state.cx(0, 1);
+        state.cx(0, 2);
+
+        if let Some(qubit) = error {
+            state.x(qubit);
+        }
+
+        // Measure the stabilizers Z0Z1 and Z1Z2 onto the ancillas
+        state.cx(0, 3);
+        state.cx(1, 3);
+        state.cx(1, 4);
+        state.cx(2, 4);
+        let syndrome = (state.measure(3).is_one(), state.measure(4).is_one());
+
+        let flipped = match syndrome {
+            (false, false) => None,
+            (true, false) => Some(0),
+            (true, true) => Some(1),
+            (false, true) => Some(2),
+        };
+        if let Some(qubit) = flipped {
+            state.x(qubit);
+        }
+        assert_eq!(flipped, error);
+
+        // Decode back onto qubit 0
+        state.cx(0, 2);
+        state.cx(0, 1);
+
+        println!("error: {error:?}, syndrome: {syndrome:?}, corrected: {flipped:?}");
+        print!("{}", state.ket().unwrap());
+        assert!(state.measure(0).is_one());
+    }
+}
@@ -0,0 +1,81 @@
+use circus::State;
+
+/// Prepare a single-qubit state on a qubit of a `State`.
+type Prepare = fn(&mut State, usize);
+
+/// Preparations of the six single-qubit stabilizer states.
+const INPUTS: [(&str, Prepare); 6] = [
+    ("|0>", |_, _| {}),
+    ("|1>", |state, q| state.x(q)),
+    ("|+>", |state, q| state.h(q)),
+    ("|->", |state, q| {
+        state.x(q);
+        state.h(q);
+    }),
+    ("|+i>", |state, q| {
+        state.h(q);
+        state.p(q);
+    }),
+    ("|-i>", |state, q| {
+        state.h(q);
+        state.p(q);
+        state.z(q);
+    }),
+];
+
+/// Teleport the state made by `prepare` from qubit 0 to qubit 2,
+/// returning the final state and the two measured bits.
+fn teleport(prepare: Prepare) -> (State, bool, bool) {
+    let mut state = State::new(3);
+    prepare(&mut state, 0);
+
+    // Share a bell pair between qubits 1 and 2
+    state.h(1);
+    state.cx(1, 2);
+
+    // Measure qubit 0 and 1 in the bell basis
+    state.cx(0, 1);
+    state.h(0);
+    let m0 = state.measure(0).is_one();
+    let m1 = state.measure(1).is_one();
+
+    // Classically-conditioned corrections on qubit 2
+    if m1 {
+        state.x(2);
+    }
+    if m0 {
+        state.z(2);
+    }
+
+    (state, m0, m1)
+}
+
+fn main() {
+    for (name, prepare) in INPUTS {
+        let (state, m0, m1) = teleport(prepare);
+
+        // Qubits 0 and 1 were left in their measured basis states, and qubit 2 holds the input
+        let mut expected = State::new(3);
+        if m0 {
+            expected.x(0);
+        }
+        if m1 {
+            expected.x(1);
+        }
+        prepare(&mut expected, 2);
+        assert_eq!(state.fidelity(&expected).unwrap(), 1.);
+
+        println!(
+            "teleported {name} with measurements ({}, {})",
+            m0 as u8, m1 as u8
+        );
+        println!("{state}");
+    }
+}
+
+#[test]
+fn teleports_each_stabilizer_state() {
+    for _ in 0..10 {
+        main();
+    }
+}
@@ -0,0 +1,52 @@
+//! Operations on whole rows of bit words.
+
+use crate::word::Word;
+
+/// XOR the words of `src` into `dst`.
+pub(crate) fn xor_assign<W: Word>(dst: &mut [W], src: &[W]) {
+    W::xor_slice(dst, src)
+}
+
+/// Copy the interleaved x and z words of a row on `n` qubits into `dst`, which packs them into words of another type.
+pub(crate) fn repack_row<V: Word, W: Word>(n: usize, src: &[V], dst: &mut [W]) {
+    dst.fill(W::ZERO);
+    for j in 0..n {
+        let ((v5, pv), (w5, pw)) = (V::locate(j), W::locate(j));
+        for z in 0..2 {
+            if src[2 * v5 + z] & pv != V::ZERO {
+                dst[2 * w5 + z] |= pw;
+            }
+        }
+    }
+}
+
+/// XOR the words of `src` into `dst`, eight lanes at a time.
+#[cfg(all(feature = "simd", circus_nightly))]
+pub(crate) fn xor_u64x8(dst: &mut [u64], src: &[u64]) {
+    use std::simd::u64x8;
+
+    let mut dst_chunks = dst.chunks_exact_mut(8);
+    let mut src_chunks = src.chunks_exact(8);
+    for (d, s) in (&mut dst_chunks).zip(&mut src_chunks) {
+        (u64x8::from_slice(d) ^ u64x8::from_slice(s)).copy_to_slice(d);
+    }
+
+    for (d, s) in dst_chunks
+        .into_remainder()
+        .iter_mut()
+        .zip(src_chunks.remainder())
+    {
+        *d ^= s;
+    }
+}
+
+/// Borrow row `i` mutably and row `k` immutably, where `i != k`.
+pub(crate) fn row_pair<T>(rows: &mut [T], i: usize, k: usize) -> (&mut T, &T) {
+    if i < k {
+        let (head, tail) = rows.split_at_mut(k);
+        (&mut head[i], &tail[0])
+    } else {
+        let (head, tail) = rows.split_at_mut(i);
+        (&mut tail[0], &head[k])
+    }
+}
@@ -0,0 +1,203 @@
+//! Reading and writing circuits in the text format of Aaronson and Gottesman's CHP simulator.
+//!
+//! A CHP file starts with a free-form description, ended by a line beginning with `#`.
+//! Each following line is a gate: `h q` (Hadamard), `p q` (phase), `c a b` (CNOT from `a` to `b`) or `m q` (measure).
+
+use crate::{
+    clifford::Clifford,
+    gate::{CNotGate, Gates, HadamardGate, PhaseGate},
+    Instruction,
+};
+use core::fmt::{self, Write};
+
+/// Errors from reading or writing a CHP circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChpError {
+    /// A line isn't one of `h`, `p`, `c` or `m`.
+    UnknownGate {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Name of the gate.
+        name: String,
+    },
+
+    /// A gate has the wrong number of targets, or a target isn't a qubit index.
+    InvalidTargets {
+        /// Line number, starting at 1.
+        line: usize,
+    },
+
+    /// An instruction has no equivalent in CHP, such as a detector.
+    Unsupported {
+        /// Index of the instruction in the circuit.
+        index: usize,
+    },
+}
+
+impl fmt::Display for ChpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownGate { line, name } => write!(f, "line {line}: unknown gate `{name}`"),
+            Self::InvalidTargets { line } => write!(f, "line {line}: invalid targets"),
+            Self::Unsupported { index } => {
+                write!(f, "instruction {index} can't be written in CHP")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChpError {}
+
+/// Parse the CHP circuit `src`.
+///
+/// If there is no `#` line, the whole of `src` is read as gates.
+///
+/// ```
+/// use circus::{chp, Instruction};
+///
+/// let src = "Prepare a Bell pair and measure it\n#\nh 0\nc 0 1\np 1\n\nm 0\nm 1\n";
+/// assert_eq!(
+///     chp::parse(src).unwrap(),
+///     [
+///         Instruction::h(0),
+///         Instruction::cx(0, 1),
+///         Instruction::p(1),
+///         Instruction::measure(0),
+///         Instruction::measure(1),
+///     ]
+/// );
+///
+/// assert_eq!(
+///     chp::parse("#\nx 0\n"),
+///     Err(chp::ChpError::UnknownGate {
+///         line: 2,
+///         name: "x".into()
+///     })
+/// );
+/// ```
+pub fn parse(src: &str) -> Result<Vec<Instruction>, ChpError> {
+    let lines: Vec<_> = src.lines().collect();
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with('#'))
+        .map_or(0, |i| i + 1);
+
+    let mut instructions = Vec::new();
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        let number = i + 1;
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            continue;
+        };
+        let targets = words
+            .map(str::parse)
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| ChpError::InvalidTargets { line: number })?;
+
+        let instruction = match (name.to_ascii_lowercase().as_str(), &targets[..]) {
+            ("h", &[target]) => Instruction::h(target),
+            ("p", &[target]) => Instruction::p(target),
+            ("c", &[control, target]) if control != target => Instruction::cx(control, target),
+            ("m", &[target]) => Instruction::measure(target),
+            ("h" | "p" | "c" | "m", _) => return Err(ChpError::InvalidTargets { line: number }),
+            _ => {
+                return Err(ChpError::UnknownGate {
+                    line: number,
+                    name: name.to_owned(),
+                })
+            }
+        };
+        instructions.push(instruction);
+    }
+    Ok(instructions)
+}
+
+/// Write a circuit on `n` qubits in the CHP format.
+///
+/// Gates other than H, S and CNOT are written as equivalent sequences of them, up to a global phase.
+/// Detectors can't be written and return [`ChpError::Unsupported`].
+///
+/// ```
+/// use circus::{chp, Instruction, State};
+///
+/// let circuit = [
+///     Instruction::h(0),
+///     Instruction::cz(0, 1),
+///     Instruction::iswap(1, 2),
+///     Instruction::y(2),
+///     Instruction::measure(2),
+/// ];
+/// let src = chp::to_string(3, &circuit).unwrap();
+/// assert!(src.starts_with("circus circuit on 3 qubits\n#\nh 0\n"));
+///
+/// let mut a = State::new(3);
+/// a.run(circuit[..4].iter().cloned()).for_each(drop);
+/// let mut b = State::new(3);
+/// b.run(chp::parse(&src).unwrap()).for_each(drop);
+/// assert_eq!(a.ket().unwrap(), b.ket().unwrap());
+/// ```
+pub fn to_string(n: usize, instructions: &[Instruction]) -> Result<String, ChpError> {
+    let mut s = format!("circus circuit on {n} qubits\n#\n");
+    for (index, instruction) in instructions.iter().enumerate() {
+        let gates = match instruction {
+            Instruction::Gate(gate) => core::slice::from_ref(gate),
+            Instruction::GateBroadcast(gates) => gates,
+            Instruction::Measure { target } => {
+                writeln!(s, "m {target}").unwrap();
+                continue;
+            }
+            Instruction::Detector { .. } => return Err(ChpError::Unsupported { index }),
+        };
+        for gate in gates {
+            write_gate(&mut s, gate);
+        }
+    }
+    Ok(s)
+}
+
+/// Write `gate` as a sequence of CHP gates.
+fn write_gate(s: &mut String, gate: &Gates) {
+    let h = |s: &mut String, q| writeln!(s, "h {q}").unwrap();
+    let p = |s: &mut String, q, count| {
+        for _ in 0..count {
+            writeln!(s, "p {q}").unwrap();
+        }
+    };
+
+    match *gate {
+        Gates::Hadamard(HadamardGate { target }) => h(s, target),
+        Gates::Phase(PhaseGate { target }) => p(s, target, 1),
+        Gates::CNot(CNotGate { control, target }) => writeln!(s, "c {control} {target}").unwrap(),
+        // S† = S^3 and Z = S^2
+        Gates::PhaseDagger(gate) => p(s, gate.target, 3),
+        Gates::PauliZ(gate) => p(s, gate.target, 2),
+        // X = H Z H, and Y = i X Z
+        Gates::PauliX(gate) => {
+            h(s, gate.target);
+            p(s, gate.target, 2);
+            h(s, gate.target);
+        }
+        Gates::PauliY(gate) => {
+            p(s, gate.target, 2);
+            h(s, gate.target);
+            p(s, gate.target, 2);
+            h(s, gate.target);
+        }
+        // CZ = (I ⊗ H) CX (I ⊗ H)
+        Gates::CZ(gate) => {
+            h(s, gate.b);
+            writeln!(s, "c {} {}", gate.a, gate.b).unwrap();
+            h(s, gate.b);
+        }
+        // Synthesize the two-qubit operator and relabel its qubits
+        Gates::ISwap(gate) => {
+            let local = Gates::ISwap(crate::gate::ISwapGate { a: 0, b: 1 });
+            let qubits = [gate.a, gate.b];
+            for instruction in Clifford::from_gates(2, &[local]).to_circuit() {
+                if let Instruction::Gate(gate) = instruction.map_qubits(|q| qubits[q]) {
+                    write_gate(s, &gate);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{Instruction, Measurement, State};
+use core::{iter::Cloned, slice};
+use std::vec;
+
+/// A circuit that can be run any number of times, each on a fresh state.
+///
+/// ```
+/// use circus::{Circuit, Instruction, State};
+///
+/// let instructions = [
+///     Instruction::x(0),
+///     Instruction::cx(0, 1),
+///     Instruction::h(2),
+///     Instruction::h(2),
+///     Instruction::measure(0),
+///     Instruction::measure(1),
+///     Instruction::measure(2),
+/// ];
+/// let circuit: Circuit = instructions.iter().cloned().collect();
+/// assert_eq!(circuit.len(), 7);
+///
+/// let mut state = State::new(3);
+/// let expected: Vec<_> = state.run(instructions).map(|m| m.as_u8()).collect();
+/// for _ in 0..3 {
+///     let measurements: Vec<_> = circuit.run(3).iter().map(|m| m.as_u8()).collect();
+///     assert_eq!(measurements, expected);
+/// }
+///
+/// // A circuit can also be run directly on a state, by reference or by value
+/// let by_ref: Vec<_> = State::new(3).run(&circuit).collect();
+/// let by_value: Vec<_> = State::new(3).run(circuit).collect();
+/// assert_eq!(by_ref, by_value);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Circuit {
+    instructions: Vec<Instruction>,
+}
+
+impl Circuit {
+    /// Create an empty circuit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of instructions.
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    /// Returns `true` if this circuit has no instructions.
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    /// Append an instruction.
+    pub fn push(&mut self, instruction: impl Into<Instruction>) {
+        self.instructions.push(instruction.into());
+    }
+
+    /// The instructions of this circuit, in order.
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Run this circuit on a fresh state of `n` qubits and return its measurements.
+    pub fn run(&self, n: usize) -> Vec<Measurement> {
+        let mut state = State::new(n);
+        state.run(self).collect()
+    }
+}
+
+impl From<Vec<Instruction>> for Circuit {
+    fn from(instructions: Vec<Instruction>) -> Self {
+        Self { instructions }
+    }
+}
+
+impl FromIterator<Instruction> for Circuit {
+    fn from_iter<I: IntoIterator<Item = Instruction>>(iter: I) -> Self {
+        Self {
+            instructions: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Instruction> for Circuit {
+    fn extend<I: IntoIterator<Item = Instruction>>(&mut self, iter: I) {
+        self.instructions.extend(iter);
+    }
+}
+
+impl IntoIterator for Circuit {
+    type Item = Instruction;
+    type IntoIter = vec::IntoIter<Instruction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.instructions.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Circuit {
+    type Item = Instruction;
+    type IntoIter = Cloned<slice::Iter<'a, Instruction>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.instructions.iter().cloned()
+    }
+}
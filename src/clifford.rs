@@ -0,0 +1,613 @@
+//! Clifford operators.
+
+use crate::{
+    bits::{repack_row, xor_assign},
+    gate::{
+        CNotGate, Gates, HadamardGate, PauliXGate, PauliYGate, PauliZGate, PhaseDaggerGate,
+        PhaseGate,
+    },
+    state::phase_exponent,
+    Error, Instruction, State, Word,
+};
+use core::fmt;
+use rand::Rng;
+
+/// Clifford operator on `n` qubits, stored as the tableau of images `U X_j U†` and `U Z_j U†`
+/// of each qubit's Pauli operators (with their signs).
+///
+/// This reuses the layout of a [`State`]: starting from the tableau of `State::new(n)`,
+/// whose destabilizers and stabilizers are `X_j` and `Z_j`, applying the gates of a circuit
+/// leaves the images of those operators under the circuit.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Clifford {
+    tableau: State,
+}
+
+impl Clifford {
+    /// The identity operator on `n` qubits.
+    pub fn identity(n: usize) -> Self {
+        Self {
+            tableau: State::new(n),
+        }
+    }
+
+    /// Fold a sequence of gates on `n` qubits into a single operator.
+    pub fn from_gates(n: usize, gates: &[Gates]) -> Self {
+        let mut clifford = Self::identity(n);
+        for gate in gates {
+            clifford.tableau.apply_gate(gate);
+        }
+        clifford
+    }
+
+    /// Fold a circuit of gates on `n` qubits into a single operator.
+    ///
+    /// This returns [`Error::NonClifford`] if the circuit contains a measurement,
+    /// or [`Error::Invalid`] if it fails [`validate`](crate::validate).
+    ///
+    /// ```
+    /// use circus::{clifford::Clifford, prep, Error, Instruction, State, ValidationError};
+    ///
+    /// let circuit = prep::graph_state(&[(0, 1), (1, 2)], 3);
+    /// let clifford = Clifford::from_instructions(3, &circuit).unwrap();
+    ///
+    /// let mut a = State::new(3);
+    /// clifford.apply_to(&mut a).unwrap();
+    ///
+    /// let mut b = State::new(3);
+    /// b.run(circuit).for_each(drop);
+    ///
+    /// assert!(a == b);
+    ///
+    /// let measure = [Instruction::Measure { target: 0 }];
+    /// assert_eq!(
+    ///     Clifford::from_instructions(1, &measure).err(),
+    ///     Some(Error::NonClifford { index: 0 })
+    /// );
+    /// assert_eq!(
+    ///     Clifford::from_instructions(2, &prep::ghz(3)).err(),
+    ///     Some(Error::Invalid(ValidationError::QubitOutOfRange { index: 2, qubit: 2 }))
+    /// );
+    /// ```
+    pub fn from_instructions(n: usize, instructions: &[Instruction]) -> Result<Self, Error> {
+        crate::validate(n, instructions)?;
+        let mut clifford = Self::identity(n);
+        for (index, instruction) in instructions.iter().enumerate() {
+            match instruction {
+                Instruction::Gate(gate) => clifford.tableau.apply_gate(gate),
+                Instruction::GateBroadcast(gates) => gates
+                    .iter()
+                    .for_each(|gate| clifford.tableau.apply_gate(gate)),
+                Instruction::Measure { .. } => return Err(Error::NonClifford { index }),
+                Instruction::Detector { .. } => {}
+            }
+        }
+        Ok(clifford)
+    }
+
+    /// Synthesize a circuit of H, S, CNOT, X and Z gates implementing this operator,
+    /// in the 11-stage canonical form H-C-P-C-P-C-H-P-C-P-C of Aaronson and Gottesman.
+    ///
+    /// Each stage is a layer of only Hadamards (H), only CNOTs (C) or only phase gates (P),
+    /// and the circuit ends with a layer of Paulis fixing the signs. The stages reduce the tableau
+    /// of the inverse operator to the identity, so applying them in order gives this operator.
+    ///
+    /// ```
+    /// use circus::{clifford::Clifford, gate::Gates, rb, Instruction};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let mut rng = StdRng::seed_from_u64(0);
+    /// let mut circuit = rb::rb_sequence(4, 10, &mut rng);
+    /// circuit.pop(); // Drop the inversion
+    ///
+    /// let clifford = Clifford::from_instructions(4, &circuit).unwrap();
+    /// let synthesized = clifford.to_circuit();
+    /// assert!(Clifford::from_instructions(4, &synthesized).unwrap() == clifford);
+    ///
+    /// let mut stages: Vec<_> = synthesized
+    ///     .iter()
+    ///     .map(|instruction| match instruction {
+    ///         Instruction::Gate(Gates::Hadamard(_)) => 'H',
+    ///         Instruction::Gate(Gates::CNot(_)) => 'C',
+    ///         Instruction::Gate(Gates::Phase(_)) => 'P',
+    ///         Instruction::Gate(Gates::PauliX(_) | Gates::PauliZ(_)) => 'X',
+    ///         _ => unreachable!(),
+    ///     })
+    ///     .collect();
+    /// stages.dedup();
+    ///
+    /// // Empty layers are skipped, so the stages are a subsequence of the canonical form
+    /// let mut canonical = "HCPCPCHPCPCX".chars();
+    /// assert!(stages.iter().all(|&stage| canonical.any(|c| c == stage)));
+    /// ```
+    pub fn to_circuit(&self) -> Vec<Instruction> {
+        let n = self.tableau.n;
+        let mut reduction = Reduction {
+            tableau: self.inverse().tableau,
+            gates: Vec::new(),
+        };
+
+        // The stabilizers are (C | D) for their x bits C and z bits D.
+        // Make C invertible, then reduce them to (I | 0) and with Hadamards to (0 | I)
+        reduction.h_non_pivots();
+        reduction.eliminate_x(n);
+        reduction.clear_z(n);
+        reduction.eliminate_x(n);
+        (0..n).for_each(|q| reduction.h(q));
+
+        // Commuting with the stabilizers now leaves the destabilizers as (I | B), reduced the same way
+        reduction.clear_z(0);
+        reduction.eliminate_x(0);
+
+        for q in 0..n {
+            if reduction.tableau.r[q] == 2 {
+                reduction.apply(Gates::PauliZ(PauliZGate { target: q }));
+            }
+            if reduction.tableau.r[q + n] == 2 {
+                reduction.apply(Gates::PauliX(PauliXGate { target: q }));
+            }
+        }
+        debug_assert!(reduction.tableau == State::new(n));
+
+        reduction.gates.into_iter().map(Instruction::Gate).collect()
+    }
+
+    /// Build the operator mapping each `X_j` and `Z_j` to the images given by the rows of `matrix`,
+    /// with the phase exponents `signs` (0 for `+`, 2 for `-`).
+    ///
+    /// Returns [`Error::NotSymplectic`] if the images don't have the commutation relations of the
+    /// operators they replace, [`Error::NonHermitian`] for an odd sign,
+    /// or [`Error::DimensionMismatch`] if there aren't `2n` signs.
+    pub fn from_symplectic(matrix: &SymplecticMatrix, signs: &[i32]) -> Result<Self, Error> {
+        let n = matrix.n;
+        if signs.len() != 2 * n {
+            return Err(Error::DimensionMismatch {
+                expected: 2 * n,
+                found: signs.len(),
+            });
+        }
+        if signs.iter().any(|r| r % 2 != 0) {
+            return Err(Error::NonHermitian);
+        }
+        if !matrix.is_symplectic() {
+            return Err(Error::NotSymplectic);
+        }
+
+        let mut tableau = State::new(n);
+        for (i, sign) in signs.iter().enumerate() {
+            tableau.xz[i].fill(0);
+            tableau.r[i] = sign.rem_euclid(4);
+            for j in 0..n {
+                let (j5, pw) = u64::locate(j);
+                if matrix.get(i, j) {
+                    *tableau.x_word_mut(i, j5) |= pw;
+                }
+                if matrix.get(i, j + n) {
+                    *tableau.z_word_mut(i, j5) |= pw;
+                }
+            }
+        }
+        Ok(Self { tableau })
+    }
+
+    /// Number of qubits this operator acts on.
+    pub fn num_qubits(&self) -> usize {
+        self.tableau.n
+    }
+
+    /// Compose this operator with `other`, applying this operator first.
+    ///
+    /// Returns an error if the operators act on different numbers of qubits.
+    ///
+    /// ```
+    /// use circus::{
+    ///     clifford::Clifford,
+    ///     gate::{Gates, HadamardGate, PhaseGate},
+    ///     Error, State,
+    /// };
+    ///
+    /// let h = Clifford::from_gates(1, &[Gates::Hadamard(HadamardGate { target: 0 })]);
+    /// let s = Clifford::from_gates(1, &[Gates::Phase(PhaseGate { target: 0 })]);
+    ///
+    /// let mut a = State::new(1);
+    /// h.compose(&s).unwrap().apply_to(&mut a).unwrap();
+    ///
+    /// let mut b = State::new(1);
+    /// b.h(0);
+    /// b.p(0);
+    ///
+    /// assert!(a == b);
+    ///
+    /// assert_eq!(
+    ///     h.compose(&Clifford::identity(2)).err(),
+    ///     Some(Error::DimensionMismatch { expected: 2, found: 1 })
+    /// );
+    /// ```
+    pub fn compose(&self, other: &Self) -> Result<Self, Error> {
+        let mut tableau = self.tableau.clone();
+        other.apply_to(&mut tableau)?;
+        Ok(Self { tableau })
+    }
+
+    /// The inverse of this operator, which maps each image of `X_j` and `Z_j` back to `X_j` and `Z_j`.
+    ///
+    /// ```
+    /// use circus::{clifford::Clifford, rb};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let mut rng = StdRng::seed_from_u64(1);
+    /// let mut circuit = rb::rb_sequence(5, 10, &mut rng);
+    /// circuit.pop(); // Drop the inversion
+    ///
+    /// let clifford = Clifford::from_instructions(5, &circuit).unwrap();
+    /// let inverse = clifford.inverse();
+    /// assert!(clifford.compose(&inverse).unwrap() == Clifford::identity(5));
+    /// assert!(inverse.compose(&clifford).unwrap() == Clifford::identity(5));
+    /// ```
+    pub fn inverse(&self) -> Self {
+        let t = &self.tableau;
+        let n = t.n;
+
+        // Column `c` of the symplectic matrix is the x bit of qubit `c` for `c < n`,
+        // and otherwise the z bit of qubit `c - n`
+        let word = |c: usize| {
+            let (j, z) = if c < n { (c, 0) } else { (c - n, 1) };
+            let (j5, pw) = u64::locate(j);
+            (2 * j5 + z, pw)
+        };
+        let swap = |c: usize| if c < n { c + n } else { c - n };
+
+        // The inverse of a symplectic matrix M is ΩMᵀΩ, where Ω swaps the x and z halves
+        let mut inverse = State::new(n);
+        for a in 0..2 * n {
+            inverse.xz[a].fill(0);
+            for b in 0..2 * n {
+                let (w, pw) = word(swap(a));
+                if t.xz[swap(b)][w] & pw > 0 {
+                    let (w, pw) = word(b);
+                    inverse.xz[a][w] |= pw;
+                }
+            }
+        }
+        let mut inverse = Self { tableau: inverse };
+
+        // Undoing this operator now leaves each X_j and Z_j with a sign, which a Pauli corrects
+        let signs = self.compose(&inverse).unwrap().tableau;
+        for q in 0..n {
+            if signs.r[q] == 2 {
+                inverse.tableau.z(q);
+            }
+            if signs.r[q + n] == 2 {
+                inverse.tableau.x(q);
+            }
+        }
+        inverse
+    }
+
+    /// Apply this operator to a `state` on the same number of qubits,
+    /// conjugating each of its destabilizer and stabilizer rows.
+    ///
+    /// The state can be packed into any word type.
+    /// Returns an error, leaving `state` unchanged, if it has a different number of qubits.
+    ///
+    /// ```
+    /// use circus::{clifford::Clifford, prep, State};
+    ///
+    /// let ghz = Clifford::from_instructions(40, &prep::ghz(40)).unwrap();
+    ///
+    /// let mut narrow = State::<u32>::packed(40);
+    /// ghz.apply_to(&mut narrow).unwrap();
+    ///
+    /// let mut wide = State::new(40);
+    /// wide.run(prep::ghz(40)).for_each(drop);
+    /// assert!(narrow.repack::<u64>() == wide);
+    /// ```
+    pub fn apply_to<W: Word>(&self, state: &mut State<W>) -> Result<(), Error> {
+        let n = self.tableau.n;
+        if state.n != n {
+            return Err(Error::DimensionMismatch {
+                expected: n,
+                found: state.n,
+            });
+        }
+
+        let t = &self.tableau;
+        let mut xz = vec![0; 2 * t.words];
+
+        for i in 0..2 * n {
+            xz.fill(0);
+            let mut r = state.r[i];
+
+            for j in 0..n {
+                let has_x = state.x_bit(i, j);
+                let has_z = state.z_bit(i, j);
+
+                // Y = iXZ, so its image is i times the images of X and Z
+                if has_x && has_z {
+                    r += 1;
+                }
+                if has_x {
+                    r += phase_exponent(&t.xz[j], &xz) + t.r[j];
+                    xor_assign(&mut xz, &t.xz[j]);
+                }
+                if has_z {
+                    r += phase_exponent(&t.xz[j + n], &xz) + t.r[j + n];
+                    xor_assign(&mut xz, &t.xz[j + n]);
+                }
+            }
+
+            repack_row(n, &xz, &mut state.xz[i]);
+            state.r[i] = r.rem_euclid(4);
+        }
+        Ok(())
+    }
+}
+
+/// Display the images of `X_j` and then `Z_j` for each qubit `j`.
+impl fmt::Display for Clifford {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tableau.fmt(f)
+    }
+}
+
+/// A tableau being reduced to the identity, recording the gates applied to it.
+struct Reduction {
+    tableau: State,
+    gates: Vec<Gates>,
+}
+
+impl Reduction {
+    fn apply(&mut self, gate: Gates) {
+        self.tableau.apply_gate(&gate);
+        self.gates.push(gate);
+    }
+
+    fn x(&self, row: usize, j: usize) -> bool {
+        self.tableau.x_bit(row, j)
+    }
+
+    fn z(&self, row: usize, j: usize) -> bool {
+        self.tableau.z_bit(row, j)
+    }
+
+    fn h(&mut self, target: usize) {
+        self.apply(Gates::Hadamard(HadamardGate { target }));
+    }
+
+    fn s(&mut self, target: usize) {
+        self.apply(Gates::Phase(PhaseGate { target }));
+    }
+
+    fn cx(&mut self, control: usize, target: usize) {
+        self.apply(Gates::CNot(CNotGate { control, target }));
+    }
+
+    /// Apply Hadamards making the x bits of the stabilizers invertible,
+    /// on each column that isn't a pivot of their row echelon form.
+    fn h_non_pivots(&mut self) {
+        let n = self.tableau.n;
+        let mut rows: Vec<Box<[u64]>> = (n..2 * n).map(|i| self.tableau.xz[i].into()).collect();
+
+        let mut pivot = 0;
+        for j in 0..n {
+            let (j5, pw) = u64::locate(j);
+            let w = 2 * j5;
+            let Some(k) = (pivot..n).find(|&k| rows[k][w] & pw > 0) else {
+                self.h(j);
+                continue;
+            };
+            rows.swap(pivot, k);
+            for k in pivot + 1..n {
+                if rows[k][w] & pw > 0 {
+                    let (row, other) = crate::bits::row_pair(&mut rows, k, pivot);
+                    xor_assign(row, other);
+                }
+            }
+            pivot += 1;
+        }
+    }
+
+    /// Reduce the invertible x bits of rows `offset..offset + n` to the identity with CNOTs,
+    /// which add one column to another.
+    fn eliminate_x(&mut self, offset: usize) {
+        let n = self.tableau.n;
+        for i in 0..n {
+            let row = offset + i;
+            if !self.x(row, i) {
+                if let Some(j) = (i + 1..n).find(|&j| self.x(row, j)) {
+                    self.cx(j, i);
+                }
+            }
+            for j in 0..n {
+                if j != i && self.x(row, j) {
+                    self.cx(i, j);
+                }
+            }
+        }
+    }
+
+    /// Clear the z bits of rows `offset..offset + n`, whose x bits are the identity,
+    /// with a layer of phase gates, a layer of CNOTs and another layer of phase gates.
+    ///
+    /// The z bits form a symmetric matrix `D`, since the rows commute. The first phase gates flip its
+    /// diagonal so that `D = MMᵀ` for a unit lower triangular `M`, and the CNOTs multiply the x bits
+    /// by `M` and the z bits by `M⁻ᵀ`, leaving both as `M` for the last phase gates to cancel.
+    fn clear_z(&mut self, offset: usize) {
+        let n = self.tableau.n;
+        let words = n / u64::WIDTH + 1;
+        let bit = |row: &[u64], j: usize| {
+            let (j5, pw) = u64::locate(j);
+            row[j5] & pw > 0
+        };
+        let parity = |a: &[u64], b: &[u64]| {
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| (a & b).count_ones())
+                .sum::<u32>()
+                & 1
+                == 1
+        };
+
+        // Below the diagonal, D[i][j] is the sum of M[i][k] M[j][k] over k <= j
+        let mut m = vec![vec![0; words]; n];
+        for i in 0..n {
+            for j in 0..i {
+                if self.z(offset + i, j) != parity(&m[i], &m[j]) {
+                    let (j5, pw) = u64::locate(j);
+                    m[i][j5] |= pw;
+                }
+            }
+            let (i5, pw) = u64::locate(i);
+            m[i][i5] |= pw;
+        }
+
+        for (j, row) in m.iter().enumerate() {
+            if self.z(offset + j, j) != parity(row, row) {
+                self.s(j);
+            }
+        }
+        for b in 0..n {
+            for (a, row) in m.iter().enumerate().skip(b + 1) {
+                if bit(row, b) {
+                    self.cx(a, b);
+                }
+            }
+        }
+        (0..n).for_each(|q| self.s(q));
+    }
+}
+
+/// A `2n × 2n` matrix over GF(2) whose rows are the images of `X_0..X_{n-1}` and then `Z_0..Z_{n-1}`.
+///
+/// Each row is a Pauli operator written as its x bits for columns `0..n`, followed by its z bits for columns `n..2n`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymplecticMatrix {
+    n: usize,
+    bits: Vec<bool>,
+}
+
+impl SymplecticMatrix {
+    /// The identity matrix for `n` qubits.
+    pub fn identity(n: usize) -> Self {
+        let mut bits = vec![false; 4 * n * n];
+        for i in 0..2 * n {
+            bits[i * 2 * n + i] = true;
+        }
+        Self { n, bits }
+    }
+
+    /// Number of qubits this matrix acts on.
+    pub fn num_qubits(&self) -> usize {
+        self.n
+    }
+
+    /// Entry `(i, j)`.
+    pub fn get(&self, i: usize, j: usize) -> bool {
+        self.bits[i * 2 * self.n + j]
+    }
+
+    /// Set entry `(i, j)`.
+    pub fn set(&mut self, i: usize, j: usize, bit: bool) {
+        self.bits[i * 2 * self.n + j] = bit;
+    }
+
+    /// Returns `true` if rows `i` and `k` anticommute as Pauli operators.
+    fn anticommutes(&self, i: usize, k: usize) -> bool {
+        let n = self.n;
+        (0..n).fold(false, |parity, j| {
+            parity ^ (self.get(i, j) & self.get(k, j + n)) ^ (self.get(i, j + n) & self.get(k, j))
+        })
+    }
+
+    /// Returns `true` if the rows anticommute exactly like the pairs `X_j` and `Z_j` they replace,
+    /// which is `M Ω Mᵀ = Ω` for the symplectic form `Ω`.
+    pub fn is_symplectic(&self) -> bool {
+        let n = self.n;
+        (0..2 * n).all(|i| (i + 1..2 * n).all(|k| self.anticommutes(i, k) == (k == i + n)))
+    }
+}
+
+/// One of the 24 single-qubit Clifford operators.
+///
+/// Each is stored as an index `0..24`, made up of one of six axis permutations
+/// (`I`, `H`, `S`, `H S`, `S H`, `H S H`, applied left to right) followed by a Pauli (`I`, `X`, `Y`, `Z`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SingleQubitClifford {
+    index: u8,
+}
+
+impl SingleQubitClifford {
+    /// Number of single-qubit Clifford operators.
+    pub const COUNT: u8 = 24;
+
+    /// The identity operator.
+    pub const IDENTITY: Self = Self { index: 0 };
+
+    /// Create the Clifford with the given `index`, or `None` if it isn't less than [`Self::COUNT`].
+    pub const fn new(index: u8) -> Option<Self> {
+        if index < Self::COUNT {
+            Some(Self { index })
+        } else {
+            None
+        }
+    }
+
+    /// Choose a uniformly random single-qubit Clifford.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self {
+            index: rng.gen_range(0..Self::COUNT),
+        }
+    }
+
+    /// The index of this Clifford in `0..24`.
+    pub const fn index(self) -> u8 {
+        self.index
+    }
+
+    /// The gates applying this Clifford to `target`, in order.
+    ///
+    /// ```
+    /// use circus::{clifford::SingleQubitClifford, State};
+    ///
+    /// for index in 0..SingleQubitClifford::COUNT {
+    ///     let clifford = SingleQubitClifford::new(index).unwrap();
+    ///     let mut state = State::new(1);
+    ///     for gate in clifford.gates(0).iter().chain(&clifford.inverse_gates(0)) {
+    ///         state.apply_gate(gate);
+    ///     }
+    ///     assert_eq!(state.to_string(), State::new(1).to_string());
+    /// }
+    /// ```
+    pub fn gates(self, target: usize) -> Vec<Gates> {
+        let h = Gates::Hadamard(HadamardGate { target });
+        let s = Gates::Phase(PhaseGate { target });
+
+        let mut gates = match self.index / 4 {
+            0 => vec![],
+            1 => vec![h],
+            2 => vec![s],
+            3 => vec![h, s],
+            4 => vec![s, h],
+            _ => vec![h, s, h],
+        };
+        match self.index % 4 {
+            1 => gates.push(Gates::PauliX(PauliXGate { target })),
+            2 => gates.push(Gates::PauliY(PauliYGate { target })),
+            3 => gates.push(Gates::PauliZ(PauliZGate { target })),
+            _ => {}
+        }
+        gates
+    }
+
+    /// The gates applying the inverse of this Clifford to `target`, in order.
+    pub fn inverse_gates(self, target: usize) -> Vec<Gates> {
+        let mut gates = self.gates(target);
+        gates.reverse();
+        for gate in &mut gates {
+            if let Gates::Phase(PhaseGate { target }) = *gate {
+                *gate = Gates::PhaseDagger(PhaseDaggerGate { target });
+            }
+        }
+        gates
+    }
+}
@@ -0,0 +1,128 @@
+//! T-count accounting for Clifford+T circuits.
+//!
+//! A [`State`](crate::State) can only hold stabilizer states, so T gates can't be simulated here.
+//! These circuits are for resource estimation, where the number of T gates a decomposition needs
+//! matters more than running it. [`State::ccz_via_t`](crate::State::ccz_via_t) counts the T gates of [`ccz`]
+//! while applying the CCZ itself where the result is still a stabilizer state.
+
+use crate::gate::{
+    CNotGate, CZGate, Gates, ISwapGate, PauliXGate, PauliYGate, PauliZGate, PhaseDaggerGate,
+    PhaseGate,
+};
+
+/// A gate in a Clifford+T circuit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CliffordTGate {
+    Clifford(Gates),
+    T { target: usize },
+    TDagger { target: usize },
+}
+
+impl From<Gates> for CliffordTGate {
+    fn from(gate: Gates) -> Self {
+        Self::Clifford(gate)
+    }
+}
+
+/// Number of T and T† gates in `circuit`.
+pub fn t_count(circuit: &[CliffordTGate]) -> usize {
+    circuit
+        .iter()
+        .filter(|gate| {
+            matches!(
+                gate,
+                CliffordTGate::T { .. } | CliffordTGate::TDagger { .. }
+            )
+        })
+        .count()
+}
+
+/// Controlled-controlled-Z on `a`, `b` and `c` with 7 T gates and 6 CNOTs.
+///
+/// This uses `4abc = a + b + c - (a⊕b) - (b⊕c) - (a⊕c) + (a⊕b⊕c)`,
+/// applying T or T† to each parity in turn and undoing the CNOTs at the end.
+///
+/// ```
+/// use circus::clifford_t::{self, CliffordTGate};
+///
+/// let ccz = clifford_t::ccz(0, 1, 2);
+/// assert_eq!(clifford_t::t_count(&ccz), 7);
+/// assert_eq!(
+///     ccz.iter()
+///         .filter(|gate| matches!(gate, CliffordTGate::Clifford(_)))
+///         .count(),
+///     6
+/// );
+///
+/// for bits in 0..8 {
+///     let phase = if bits == 0b111 { 4 } else { 0 };
+///     assert_eq!(clifford_t::apply_to_basis(&ccz, bits), Some((bits, phase)));
+/// }
+/// ```
+pub fn ccz(a: usize, b: usize, c: usize) -> Vec<CliffordTGate> {
+    let cx = |control, target| CliffordTGate::Clifford(Gates::CNot(CNotGate { control, target }));
+    let t = |target| CliffordTGate::T { target };
+    let t_dag = |target| CliffordTGate::TDagger { target };
+
+    vec![
+        t(a),
+        t(b),
+        t(c),
+        // b = a⊕b
+        cx(a, b),
+        t_dag(b),
+        // c = a⊕b⊕c
+        cx(b, c),
+        t(c),
+        // c = b⊕c
+        cx(a, c),
+        t_dag(c),
+        // c = a⊕c
+        cx(b, c),
+        t_dag(c),
+        // b = b, c = c
+        cx(a, b),
+        cx(a, c),
+    ]
+}
+
+/// Run `circuit` on the computational basis state whose bit `j` is qubit `j`.
+///
+/// This returns the output basis state and its phase in eighths of a turn, so a T gate on a 1 adds 1
+/// and a Z adds 4, or `None` if a gate such as H doesn't map basis states to basis states.
+pub fn apply_to_basis(circuit: &[CliffordTGate], bits: u64) -> Option<(u64, u32)> {
+    let mut bits = bits;
+    let mut phase = 0;
+    let bit = |bits: u64, q: usize| (bits >> q & 1) as u32;
+
+    for gate in circuit {
+        match *gate {
+            CliffordTGate::T { target } => phase += bit(bits, target),
+            CliffordTGate::TDagger { target } => phase += 7 * bit(bits, target),
+            CliffordTGate::Clifford(gate) => match gate {
+                Gates::CNot(CNotGate { control, target }) => {
+                    bits ^= (bits >> control & 1) << target;
+                }
+                Gates::CZ(CZGate { a, b }) => phase += 4 * (bit(bits, a) & bit(bits, b)),
+                Gates::ISwap(ISwapGate { a, b }) => {
+                    if bit(bits, a) != bit(bits, b) {
+                        bits ^= 1 << a | 1 << b;
+                        phase += 2;
+                    }
+                }
+                Gates::PauliX(PauliXGate { target }) => bits ^= 1 << target,
+                Gates::PauliY(PauliYGate { target }) => {
+                    // Y|0> = i|1> and Y|1> = -i|0>
+                    phase += 2 + 4 * bit(bits, target);
+                    bits ^= 1 << target;
+                }
+                Gates::PauliZ(PauliZGate { target }) => phase += 4 * bit(bits, target),
+                Gates::Phase(PhaseGate { target }) => phase += 2 * bit(bits, target),
+                Gates::PhaseDagger(PhaseDaggerGate { target }) => phase += 6 * bit(bits, target),
+                Gates::Hadamard(_) => return None,
+            },
+        }
+    }
+
+    Some((bits, phase % 8))
+}
@@ -0,0 +1,119 @@
+//! Drawing circuits as ASCII wire diagrams.
+
+use crate::{
+    gate::{
+        CNotGate, CZGate, Gates, HadamardGate, ISwapGate, PauliXGate, PauliYGate, PauliZGate,
+        PhaseDaggerGate, PhaseGate,
+    },
+    Instruction,
+};
+
+/// Draw `instructions` on `n` qubits as a wire diagram, with one column per gate or measurement.
+///
+/// Each qubit is a horizontal wire of `-`, with single-qubit gates drawn by name (`Sdg` for `S†`)
+/// and `M` for measurements.
+/// A CNOT is drawn as `@` on its control and `X` on its target, a CZ as `@` on both qubits
+/// and an iSWAP as `iSWAP` on both, joined by `|` across the gaps between wires and `+` over any wire in between.
+/// Broadcast gate groups are drawn one gate at a time, and detectors are skipped.
+///
+/// # Panics
+///
+/// Panics if an instruction acts on a qubit that isn't less than `n`.
+///
+/// ```
+/// use circus::{diagram, prep, Instruction};
+///
+/// let mut bell = prep::ghz(2);
+/// bell.extend([Instruction::measure(0), Instruction::measure(1)]);
+/// assert_eq!(
+///     diagram::draw(2, &bell),
+///     "q0: -H-@-M---\n       |\nq1: ---X---M-\n"
+/// );
+///
+/// let circuit = [Instruction::cz(0, 2), Instruction::s_dag(1)];
+/// assert_eq!(
+///     diagram::draw(3, &circuit),
+///     "q0: -@-----\n     |\nq1: -+-Sdg-\n     |\nq2: -@-----\n"
+/// );
+/// ```
+pub fn draw(n: usize, instructions: &[Instruction]) -> String {
+    let label_width = format!("q{}:", n.saturating_sub(1)).len() + 1;
+
+    // Even rows are wires and odd rows are the gaps between them
+    let mut rows: Vec<String> = (0..(2 * n).saturating_sub(1))
+        .map(|row| {
+            if row % 2 == 0 {
+                format!("{:<label_width$}-", format!("q{}:", row / 2))
+            } else {
+                " ".repeat(label_width + 1)
+            }
+        })
+        .collect();
+
+    let columns = instructions
+        .iter()
+        .flat_map(|instruction| match instruction {
+            Instruction::Gate(gate) => vec![Column::Gate(*gate)],
+            Instruction::GateBroadcast(gates) => gates.iter().copied().map(Column::Gate).collect(),
+            Instruction::Measure { target } => vec![Column::Measure(*target)],
+            Instruction::Detector { .. } => Vec::new(),
+        });
+
+    for column in columns {
+        let labels = column.labels();
+        let width = labels.iter().map(|(_, label)| label.len()).max().unwrap();
+        let low = labels.iter().map(|&(q, _)| q).min().unwrap();
+        let high = labels.iter().map(|&(q, _)| q).max().unwrap();
+        assert!(high < n, "qubit {high} out of range for {n} qubits");
+
+        for (row, line) in rows.iter_mut().enumerate() {
+            let (q, is_wire) = (row / 2, row % 2 == 0);
+            let between = (low * 2..high * 2).contains(&row);
+            let cell = if let Some((_, label)) = labels.iter().find(|&&(l, _)| is_wire && l == q) {
+                format!("{label:-^width$}")
+            } else if is_wire {
+                let c = if between { "+" } else { "-" };
+                format!("{c:-^width$}")
+            } else {
+                let c = if between { "|" } else { " " };
+                format!("{c:^width$}")
+            };
+            line.push_str(&cell);
+            line.push(if is_wire { '-' } else { ' ' });
+        }
+    }
+
+    let mut diagram = String::new();
+    for line in &rows {
+        diagram.push_str(line.trim_end());
+        diagram.push('\n');
+    }
+    diagram
+}
+
+/// A single column of a diagram.
+enum Column {
+    Gate(Gates),
+    Measure(usize),
+}
+
+impl Column {
+    /// The label drawn on each qubit this column acts on.
+    fn labels(&self) -> Vec<(usize, &'static str)> {
+        let gate = match *self {
+            Self::Gate(gate) => gate,
+            Self::Measure(target) => return vec![(target, "M")],
+        };
+        match gate {
+            Gates::CNot(CNotGate { control, target }) => vec![(control, "@"), (target, "X")],
+            Gates::CZ(CZGate { a, b }) => vec![(a, "@"), (b, "@")],
+            Gates::ISwap(ISwapGate { a, b }) => vec![(a, "iSWAP"), (b, "iSWAP")],
+            Gates::Hadamard(HadamardGate { target }) => vec![(target, "H")],
+            Gates::PauliX(PauliXGate { target }) => vec![(target, "X")],
+            Gates::PauliY(PauliYGate { target }) => vec![(target, "Y")],
+            Gates::PauliZ(PauliZGate { target }) => vec![(target, "Z")],
+            Gates::Phase(PhaseGate { target }) => vec![(target, "S")],
+            Gates::PhaseDagger(PhaseDaggerGate { target }) => vec![(target, "Sdg")],
+        }
+    }
+}
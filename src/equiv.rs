@@ -0,0 +1,38 @@
+//! Checking that two Clifford circuits implement the same operator.
+
+use crate::{clifford::Clifford, Error, Instruction};
+
+/// Returns `true` if the circuits `a` and `b` on `n` qubits implement the same operator, up to a global phase.
+///
+/// Both circuits are folded into a [`Clifford`] with [`Clifford::from_instructions`],
+/// so this returns [`Error::NonClifford`] if either contains a measurement.
+///
+/// ```
+/// use circus::{equiv, optimize, prep, Error, Instruction};
+///
+/// let h = Instruction::h(0);
+/// assert_eq!(equiv::are_equivalent(1, &[h.clone(), h.clone()], &[]), Ok(true));
+/// assert_eq!(equiv::are_equivalent(1, &[h.clone()], &[Instruction::x(0)]), Ok(false));
+///
+/// // S S = Z, and Y = iXZ differs from XZ only by a global phase
+/// let ss = [Instruction::p(0), Instruction::p(0)];
+/// assert_eq!(equiv::are_equivalent(1, &ss, &[Instruction::z(0)]), Ok(true));
+/// let xz = [Instruction::z(0), Instruction::x(0)];
+/// assert_eq!(equiv::are_equivalent(1, &xz, &[Instruction::y(0)]), Ok(true));
+///
+/// let mut circuit = prep::ghz(3);
+/// circuit.extend([Instruction::cx(1, 2), Instruction::cx(1, 2)]);
+/// let optimized = optimize::cancel_inverses(&circuit);
+/// assert_eq!(equiv::are_equivalent(3, &circuit, &optimized), Ok(true));
+///
+/// let measure = [Instruction::measure(0)];
+/// assert_eq!(
+///     equiv::are_equivalent(1, &[], &measure),
+///     Err(Error::NonClifford { index: 0 })
+/// );
+/// ```
+pub fn are_equivalent(n: usize, a: &[Instruction], b: &[Instruction]) -> Result<bool, Error> {
+    let a = Clifford::from_instructions(n, a)?;
+    let b = Clifford::from_instructions(n, b)?;
+    Ok(a == b)
+}
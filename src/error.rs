@@ -0,0 +1,162 @@
+use crate::ValidationError;
+use core::fmt;
+
+/// Errors returned by circus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A measurement was forced to an outcome with zero probability.
+    ImpossibleOutcome {
+        /// Index of the measurement in the circuit.
+        measurement: usize,
+    },
+
+    /// A circuit contains an instruction that isn't a Clifford gate, such as a measurement.
+    NonClifford {
+        /// Index of the instruction in the circuit.
+        index: usize,
+    },
+
+    /// A dense representation was requested for too many qubits.
+    TooManyQubits {
+        /// Number of qubits in the state.
+        n: usize,
+        /// Maximum number of qubits supported.
+        max: usize,
+    },
+
+    /// A state has too many nonzero basis states to list them all.
+    TooManyBasisStates {
+        /// Base-2 logarithm of the number of nonzero basis states.
+        nonzero: usize,
+        /// Maximum supported value of `nonzero`.
+        max: usize,
+    },
+
+    /// A circuit has more measurements than the outcomes it was given.
+    MissingOutcome {
+        /// Index of the measurement in the circuit.
+        measurement: usize,
+    },
+
+    /// A qubit index is past the end of the state.
+    QubitOutOfRange {
+        /// The missing qubit.
+        qubit: usize,
+        /// Number of qubits in the state.
+        n: usize,
+    },
+
+    /// A classical bit index is past the end of its register.
+    BitOutOfRange {
+        /// The missing bit.
+        bit: usize,
+        /// Number of bits in the register.
+        len: usize,
+    },
+
+    /// The same qubit was given twice where distinct qubits are required.
+    RepeatedQubit {
+        /// The repeated qubit.
+        qubit: usize,
+    },
+
+    /// A stabilizer generator index is past the end of the state.
+    GeneratorOutOfRange {
+        /// Index of the missing generator.
+        index: usize,
+        /// Number of generators in the state.
+        n: usize,
+    },
+
+    /// Two operands act on different numbers of qubits.
+    DimensionMismatch {
+        /// Number of qubits expected.
+        expected: usize,
+        /// Number of qubits found.
+        found: usize,
+    },
+
+    /// Fewer magic-state ancillas were given than the T gates they are needed for.
+    NotEnoughAncillas {
+        /// Number of ancillas needed.
+        needed: usize,
+        /// Number of ancillas given.
+        found: usize,
+    },
+
+    /// A non-Clifford gate would take a state outside the stabilizer states.
+    NonStabilizer,
+
+    /// A Pauli operator with an imaginary phase, which can't be measured, was given.
+    NonHermitian,
+
+    /// A circuit failed [`validate`](crate::validate).
+    Invalid(ValidationError),
+
+    /// A matrix doesn't preserve the commutation relations of the Pauli operators.
+    NotSymplectic,
+
+    /// Bytes passed to [`State::from_bytes`](crate::State::from_bytes) don't encode a tableau.
+    InvalidBytes,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ImpossibleOutcome { measurement } => {
+                write!(f, "measurement {measurement} cannot have the given outcome")
+            }
+            Self::NonClifford { index } => {
+                write!(f, "instruction {index} is not a Clifford gate")
+            }
+            Self::TooManyQubits { n, max } => {
+                write!(f, "{n} qubits is more than the maximum of {max}")
+            }
+            Self::TooManyBasisStates { nonzero, max } => {
+                write!(
+                    f,
+                    "2^{nonzero} nonzero basis states is more than the maximum of 2^{max}"
+                )
+            }
+            Self::MissingOutcome { measurement } => {
+                write!(f, "no outcome given for measurement {measurement}")
+            }
+            Self::QubitOutOfRange { qubit, n } => {
+                write!(f, "qubit {qubit} is out of range for {n} qubits")
+            }
+            Self::BitOutOfRange { bit, len } => {
+                write!(f, "bit {bit} is out of range for a register of {len} bits")
+            }
+            Self::RepeatedQubit { qubit } => write!(f, "qubit {qubit} was given more than once"),
+            Self::GeneratorOutOfRange { index, n } => {
+                write!(f, "generator {index} is out of range for {n} generators")
+            }
+            Self::DimensionMismatch { expected, found } => {
+                write!(f, "expected {expected} qubits, found {found}")
+            }
+            Self::NotEnoughAncillas { needed, found } => {
+                write!(f, "needed {needed} magic-state ancillas, found {found}")
+            }
+            Self::NonStabilizer => f.write_str("gate would leave the stabilizer states"),
+            Self::NonHermitian => f.write_str("Pauli operator has an imaginary phase"),
+            Self::Invalid(error) => error.fmt(f),
+            Self::NotSymplectic => f.write_str("matrix is not symplectic"),
+            Self::InvalidBytes => f.write_str("bytes don't encode a tableau"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Invalid(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(error: ValidationError) -> Self {
+        Self::Invalid(error)
+    }
+}
@@ -1,39 +1,52 @@
 use super::Gate;
-use crate::{State, PW};
+use crate::{State, Word};
 
-#[derive(Clone, Copy, Debug)]
+/// CNOT gate from `control` to `target`.
+///
+/// A CNOT whose `control` is its `target` has no meaning as a gate, so applying it leaves the state unchanged.
+/// [`State::try_apply_gate`] returns [`Error::RepeatedQubit`](crate::Error::RepeatedQubit) for it instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct CNotGate {
-    pub target: usize,
     pub control: usize,
+    pub target: usize,
 }
 
-impl Gate for CNotGate {
-    fn apply(&self, state: &mut State) {
-        let b5 = self.target >> 5;
-        let c5 = self.control >> 5;
-        let pwb = PW[self.target & 31];
-        let pwc = PW[self.control & 31];
+impl<W: Word> Gate<W> for CNotGate {
+    fn apply(&self, state: &mut State<W>) {
+        // XORing a column into itself would corrupt the tableau
+        if self.control == self.target {
+            return;
+        }
+
         for i in 0..2 * state.n {
-            if state.x[i][b5] & pwb > 0 {
-                state.x[i][c5] ^= pwc;
-            }
-            if state.z[i][c5] & pwc > 0 {
-                state.z[i][b5] ^= pwb;
-            }
-            if (state.x[i][b5] & pwb > 0)
-                && (state.z[i][c5] & pwc > 0)
-                && (state.x[i][c5] & pwc > 0)
-                && (state.z[i][b5] & pwb > 0)
-            {
-                state.r[i] = (state.r[i] + 2) % 4;
-            }
-            if (state.x[i][b5] & pwb > 0)
-                && (state.z[i][c5] & pwc > 0)
-                && !(state.x[i][c5] & pwc > 0)
-                && !(state.z[i][b5] & pwb > 0)
-            {
-                state.r[i] = (state.r[i] + 2) % 4;
-            }
+            cnot_row(state, i, self.control, self.target);
         }
     }
 }
+
+/// Apply a CNOT from `control` to `target` to row `i` of the tableau.
+fn cnot_row<W: Word>(state: &mut State<W>, i: usize, control: usize, target: usize) {
+    let (b5, pwb) = W::locate(control);
+    let (c5, pwc) = W::locate(target);
+
+    if state.x_word(i, b5) & pwb != W::ZERO {
+        *state.x_word_mut(i, c5) ^= pwc;
+    }
+    if state.z_word(i, c5) & pwc != W::ZERO {
+        *state.z_word_mut(i, b5) ^= pwb;
+    }
+    if (state.x_word(i, b5) & pwb != W::ZERO)
+        && (state.z_word(i, c5) & pwc != W::ZERO)
+        && (state.x_word(i, c5) & pwc != W::ZERO)
+        && (state.z_word(i, b5) & pwb != W::ZERO)
+    {
+        state.r[i] = (state.r[i] + 2) % 4;
+    }
+    if (state.x_word(i, b5) & pwb != W::ZERO)
+        && (state.z_word(i, c5) & pwc != W::ZERO)
+        && (state.x_word(i, c5) & pwc == W::ZERO)
+        && (state.z_word(i, b5) & pwb == W::ZERO)
+    {
+        state.r[i] = (state.r[i] + 2) % 4;
+    }
+}
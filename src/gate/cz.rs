@@ -0,0 +1,49 @@
+use super::{CNotGate, Gate, HadamardGate};
+use crate::{State, Word};
+
+/// Controlled-Z gate, flipping the phase of `|11⟩`.
+///
+/// Applied as the decomposition `H(b) CX(a, b) H(b)`.
+/// Like [`CNotGate`], applying it to the same qubit twice leaves the state unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CZGate {
+    pub a: usize,
+    pub b: usize,
+}
+
+impl<W: Word> Gate<W> for CZGate {
+    fn apply(&self, state: &mut State<W>) {
+        if self.a == self.b {
+            return;
+        }
+
+        HadamardGate { target: self.b }.apply(state);
+        CNotGate {
+            control: self.a,
+            target: self.b,
+        }
+        .apply(state);
+        HadamardGate { target: self.b }.apply(state);
+    }
+}
+
+/// Apply a CZ on `a` and `b` to row `i` of the tableau, in a single pass over its bits.
+pub(crate) fn cz_row<W: Word>(state: &mut State<W>, i: usize, a: usize, b: usize) {
+    let (a5, pwa) = W::locate(a);
+    let (b5, pwb) = W::locate(b);
+    let xa = state.x_word(i, a5) & pwa != W::ZERO;
+    let xb = state.x_word(i, b5) & pwb != W::ZERO;
+    let za = state.z_word(i, a5) & pwa != W::ZERO;
+    let zb = state.z_word(i, b5) & pwb != W::ZERO;
+
+    // X_a maps to X_a Z_b and X_b to Z_a X_b, so X_a X_b picks up a sign past one Z
+    if xa && xb && za != zb {
+        state.r[i] = (state.r[i] + 2) % 4;
+    }
+    if xb {
+        *state.z_word_mut(i, a5) ^= pwa;
+    }
+    if xa {
+        *state.z_word_mut(i, b5) ^= pwb;
+    }
+}
@@ -1,20 +1,20 @@
 use super::Gate;
-use crate::{State, PW};
+use crate::{State, Word};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct HadamardGate {
     pub target: usize,
 }
 
-impl Gate for HadamardGate {
-    fn apply(&self, state: &mut State) {
-        let b5 = self.target >> 5;
-        let pw = PW[self.target & 31];
+impl<W: Word> Gate<W> for HadamardGate {
+    fn apply(&self, state: &mut State<W>) {
+        let (b5, pw) = W::locate(self.target);
         for i in 0..2 * state.n {
-            let tmp = state.x[i][b5];
-            state.x[i][b5] ^= (state.x[i][b5] ^ state.z[i][b5]) & pw;
-            state.z[i][b5] ^= (state.z[i][b5] ^ tmp) & pw;
-            if (state.x[i][b5] & pw) > 0 && (state.z[i][b5] & pw) > 0 {
+            let (x, z) = (state.x_word(i, b5), state.z_word(i, b5));
+            let swapped = (x ^ z) & pw;
+            *state.x_word_mut(i, b5) ^= swapped;
+            *state.z_word_mut(i, b5) ^= swapped;
+            if x & z & pw != W::ZERO {
                 state.r[i] = (state.r[i] + 2) % 4;
             }
         }
@@ -0,0 +1,35 @@
+use super::{CNotGate, Gate, HadamardGate, PhaseGate};
+use crate::{State, Word};
+
+/// iSWAP gate, swapping qubits `a` and `b` with a phase of `i` on `|01⟩` and `|10⟩`.
+///
+/// Applied as the decomposition `S(a) S(b) H(a) CX(a, b) CX(b, a) H(b)`.
+/// Like [`CNotGate`], applying it to the same qubit twice leaves the state unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ISwapGate {
+    pub a: usize,
+    pub b: usize,
+}
+
+impl<W: Word> Gate<W> for ISwapGate {
+    fn apply(&self, state: &mut State<W>) {
+        if self.a == self.b {
+            return;
+        }
+
+        PhaseGate { target: self.a }.apply(state);
+        PhaseGate { target: self.b }.apply(state);
+        HadamardGate { target: self.a }.apply(state);
+        CNotGate {
+            control: self.a,
+            target: self.b,
+        }
+        .apply(state);
+        CNotGate {
+            control: self.b,
+            target: self.a,
+        }
+        .apply(state);
+        HadamardGate { target: self.b }.apply(state);
+    }
+}
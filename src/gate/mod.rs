@@ -1,30 +1,106 @@
 mod cnot;
 pub use cnot::CNotGate;
 
+mod cz;
+pub(crate) use cz::cz_row;
+pub use cz::CZGate;
+
 mod hadamard;
 pub use hadamard::HadamardGate;
 
+mod iswap;
+pub use iswap::ISwapGate;
+
+mod pauli;
+pub use pauli::{PauliXGate, PauliYGate, PauliZGate};
+
 mod phase;
-pub use phase::PhaseGate;
+pub use phase::{PhaseDaggerGate, PhaseGate};
 
-use crate::State;
+use crate::{State, Word};
 
-pub trait Gate {
-    fn apply(&self, state: &mut State);
+pub trait Gate<W: Word = u64> {
+    fn apply(&self, state: &mut State<W>);
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Gates {
     CNot(CNotGate),
+    CZ(CZGate),
     Hadamard(HadamardGate),
+    ISwap(ISwapGate),
+    PauliX(PauliXGate),
+    PauliY(PauliYGate),
+    PauliZ(PauliZGate),
     Phase(PhaseGate),
+    PhaseDagger(PhaseDaggerGate),
+}
+
+impl Gates {
+    /// The qubits this gate acts on.
+    pub fn qubits(&self) -> impl Iterator<Item = usize> {
+        let (a, b) = match *self {
+            Self::CNot(CNotGate { control, target }) => (control, Some(target)),
+            Self::CZ(CZGate { a, b }) | Self::ISwap(ISwapGate { a, b }) => (a, Some(b)),
+            Self::Hadamard(HadamardGate { target })
+            | Self::PauliX(PauliXGate { target })
+            | Self::PauliY(PauliYGate { target })
+            | Self::PauliZ(PauliZGate { target })
+            | Self::Phase(PhaseGate { target })
+            | Self::PhaseDagger(PhaseDaggerGate { target }) => (target, None),
+        };
+        [Some(a), b].into_iter().flatten()
+    }
+
+    /// This gate with each of its qubits `q` replaced by `f(q)`.
+    pub fn map_qubits(&self, f: impl Fn(usize) -> usize) -> Self {
+        match *self {
+            Self::CNot(CNotGate { control, target }) => Self::CNot(CNotGate {
+                control: f(control),
+                target: f(target),
+            }),
+            Self::CZ(CZGate { a, b }) => Self::CZ(CZGate { a: f(a), b: f(b) }),
+            Self::ISwap(ISwapGate { a, b }) => Self::ISwap(ISwapGate { a: f(a), b: f(b) }),
+            Self::Hadamard(HadamardGate { target }) => {
+                Self::Hadamard(HadamardGate { target: f(target) })
+            }
+            Self::PauliX(PauliXGate { target }) => Self::PauliX(PauliXGate { target: f(target) }),
+            Self::PauliY(PauliYGate { target }) => Self::PauliY(PauliYGate { target: f(target) }),
+            Self::PauliZ(PauliZGate { target }) => Self::PauliZ(PauliZGate { target: f(target) }),
+            Self::Phase(PhaseGate { target }) => Self::Phase(PhaseGate { target: f(target) }),
+            Self::PhaseDagger(PhaseDaggerGate { target }) => {
+                Self::PhaseDagger(PhaseDaggerGate { target: f(target) })
+            }
+        }
+    }
+
+    /// The inverse of this gate, if it is also a single gate.
+    pub fn inverse(&self) -> Option<Self> {
+        match *self {
+            Self::Phase(PhaseGate { target }) => {
+                Some(Self::PhaseDagger(PhaseDaggerGate { target }))
+            }
+            Self::PhaseDagger(PhaseDaggerGate { target }) => {
+                Some(Self::Phase(PhaseGate { target }))
+            }
+            Self::ISwap(_) => None,
+            gate => Some(gate),
+        }
+    }
 }
 
-impl Gate for Gates {
-    fn apply(&self, state: &mut State) {
+impl<W: Word> Gate<W> for Gates {
+    fn apply(&self, state: &mut State<W>) {
         match self {
             Self::CNot(cx) => cx.apply(state),
+            Self::CZ(cz) => cz.apply(state),
             Self::Hadamard(h) => h.apply(state),
+            Self::ISwap(iswap) => iswap.apply(state),
+            Self::PauliX(x) => x.apply(state),
+            Self::PauliY(y) => y.apply(state),
+            Self::PauliZ(z) => z.apply(state),
             Self::Phase(p) => p.apply(state),
+            Self::PhaseDagger(p) => p.apply(state),
         }
     }
 }
@@ -0,0 +1,59 @@
+use super::Gate;
+use crate::{State, Word};
+
+/// Pauli X (NOT) gate, flipping `|0⟩` and `|1⟩`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PauliXGate {
+    pub target: usize,
+}
+
+impl<W: Word> Gate<W> for PauliXGate {
+    fn apply(&self, state: &mut State<W>) {
+        let (b5, pw) = W::locate(self.target);
+
+        // X anticommutes with the Z and Y components of each row
+        for i in 0..2 * state.n {
+            if state.z_word(i, b5) & pw != W::ZERO {
+                state.r[i] = (state.r[i] + 2) % 4;
+            }
+        }
+    }
+}
+
+/// Pauli Y gate (|0⟩->i|1⟩, |1⟩->-i|0⟩).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PauliYGate {
+    pub target: usize,
+}
+
+impl<W: Word> Gate<W> for PauliYGate {
+    fn apply(&self, state: &mut State<W>) {
+        let (b5, pw) = W::locate(self.target);
+
+        // Y anticommutes with the X and Z components of each row
+        for i in 0..2 * state.n {
+            if (state.x_word(i, b5) ^ state.z_word(i, b5)) & pw != W::ZERO {
+                state.r[i] = (state.r[i] + 2) % 4;
+            }
+        }
+    }
+}
+
+/// Pauli Z gate (|0⟩->|0⟩, |1⟩->-|1⟩).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PauliZGate {
+    pub target: usize,
+}
+
+impl<W: Word> Gate<W> for PauliZGate {
+    fn apply(&self, state: &mut State<W>) {
+        let (b5, pw) = W::locate(self.target);
+
+        // Z anticommutes with the X and Y components of each row
+        for i in 0..2 * state.n {
+            if state.x_word(i, b5) & pw != W::ZERO {
+                state.r[i] = (state.r[i] + 2) % 4;
+            }
+        }
+    }
+}
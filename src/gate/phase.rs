@@ -1,20 +1,41 @@
 use super::Gate;
-use crate::{State, PW};
+use crate::{State, Word};
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct PhaseGate {
     pub target: usize,
 }
 
-impl Gate for PhaseGate {
-    fn apply(&self, state: &mut State) {
-        let b5 = self.target >> 5;
-        let pw = PW[self.target & 31];
+impl<W: Word> Gate<W> for PhaseGate {
+    fn apply(&self, state: &mut State<W>) {
+        let (b5, pw) = W::locate(self.target);
 
         for i in 0..2 * state.n {
-            if state.x[i][b5] & pw > 0 && state.z[i][b5] & pw > 0 {
+            if state.x_word(i, b5) & pw != W::ZERO && state.z_word(i, b5) & pw != W::ZERO {
                 state.r[i] = (state.r[i] + 2) % 4;
             }
-            state.z[i][b5] ^= state.x[i][b5] & pw;
+            let x = state.x_word(i, b5) & pw;
+            *state.z_word_mut(i, b5) ^= x;
+        }
+    }
+}
+
+/// Inverse of the phase gate (|0⟩->|0⟩, |1⟩->-i|1⟩).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PhaseDaggerGate {
+    pub target: usize,
+}
+
+impl<W: Word> Gate<W> for PhaseDaggerGate {
+    fn apply(&self, state: &mut State<W>) {
+        let (b5, pw) = W::locate(self.target);
+
+        for i in 0..2 * state.n {
+            if state.x_word(i, b5) & pw != W::ZERO && state.z_word(i, b5) & pw == W::ZERO {
+                state.r[i] = (state.r[i] + 2) % 4;
+            }
+            let x = state.x_word(i, b5) & pw;
+            *state.z_word_mut(i, b5) ^= x;
         }
     }
 }
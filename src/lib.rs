@@ -1,4 +1,6 @@
 //! ```
+//! use circus::State;
+//!
 //! // Create a bell state, or EPR pair, a superposition of qubits 0 and 1
 //! let mut state = State::new(2);
 //! state.h(0);
@@ -6,30 +8,189 @@
 //! state.measure(1);
 //!
 //! println!("{state}");
-//! println!("{}", state.ket());
+//! println!("{}", state.ket().unwrap());
+//! ```
+//!
+//! The gates and [`Measurement`] are also exported from the crate root:
+//!
+//! ```
+//! use circus::{Measurement, PhaseDaggerGate, PhaseGate, State};
+//! use circus::gate::Gates;
+//!
+//! let mut state = State::new(1);
+//! state.h(0);
+//! state.apply_gate(&Gates::Phase(PhaseGate { target: 0 }));
+//! state.apply_gate(&Gates::PhaseDagger(PhaseDaggerGate { target: 0 }));
+//! state.h(0);
+//!
+//! let m: Measurement = state.measure(0);
+//! assert!(m.is_zero() && !m.is_random());
 //! ```
 
+#![cfg_attr(all(feature = "simd", circus_nightly), feature(portable_simd))]
+
+mod bits;
+
+pub mod chp;
+
+mod circuit;
+pub use circuit::Circuit;
+
+pub mod clifford;
+
+pub mod clifford_t;
+
+pub mod diagram;
+
+pub mod equiv;
+
+mod error;
+pub use error::Error;
+
 pub mod gate;
-use gate::Gates;
+pub use gate::{
+    CNotGate, CZGate, Gates, HadamardGate, ISwapGate, PauliXGate, PauliYGate, PauliZGate,
+    PhaseDaggerGate, PhaseGate,
+};
 
 mod measurement;
-pub use measurement::Measurement;
+pub use measurement::{Measurement, MeasurementVec, Outcome};
+
+pub mod mixed;
+
+pub mod noise;
+
+pub mod optimize;
+
+pub mod pauli;
+
+pub mod prep;
+
+pub mod rb;
+
+pub mod shadows;
 
 pub mod state;
-pub use state::State;
+pub use state::{DefaultState, State};
+
+pub mod stats;
+
+pub mod stim;
 
+mod validate;
+pub use validate::{validate, ValidationError};
+
+#[cfg(feature = "verify")]
+pub mod verify;
+
+pub mod word;
+pub use word::Word;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Instruction {
     Gate(Gates),
-    Measure { target: usize },
+    /// A group of gates applied in order, such as `H 0 1 2` or `CX 0 1 2 3` in Stim.
+    GateBroadcast(Vec<Gates>),
+    Measure {
+        target: usize,
+    },
+    /// A parity check over earlier measurements, like Stim's `DETECTOR`,
+    /// where each index counts the measurements made so far from 0.
+    ///
+    /// This doesn't act on the state, and a noiseless circuit's detectors always read 0.
+    Detector {
+        measurement_indices: Vec<usize>,
+    },
 }
 
-// Powers of 2 (PW[i] = 2^i)
-const PW: [u64; 32] = {
-    let mut pw = [1; 32];
-    let mut i = 1;
-    while i < 32 {
-        pw[i] = 2 * pw[i - 1];
-        i += 1;
+impl Instruction {
+    /// Hadamard gate on `target`.
+    ///
+    /// ```
+    /// use circus::{
+    ///     gate::{CNotGate, Gates, HadamardGate},
+    ///     Instruction,
+    /// };
+    ///
+    /// let circuit = [
+    ///     Instruction::h(0),
+    ///     Instruction::cx(0, 1),
+    ///     Instruction::measure(1),
+    /// ];
+    /// assert_eq!(
+    ///     circuit,
+    ///     [
+    ///         Instruction::Gate(Gates::Hadamard(HadamardGate { target: 0 })),
+    ///         Instruction::Gate(Gates::CNot(CNotGate { control: 0, target: 1 })),
+    ///         Instruction::Measure { target: 1 },
+    ///     ]
+    /// );
+    /// assert_eq!(Instruction::from(Gates::Hadamard(HadamardGate { target: 0 })), circuit[0]);
+    /// ```
+    pub fn h(target: usize) -> Self {
+        Gates::Hadamard(HadamardGate { target }).into()
     }
-    pw
-};
+
+    /// CNOT gate from `control` to `target`.
+    pub fn cx(control: usize, target: usize) -> Self {
+        Gates::CNot(CNotGate { control, target }).into()
+    }
+
+    /// Controlled-Z gate on `a` and `b`.
+    pub fn cz(a: usize, b: usize) -> Self {
+        Gates::CZ(CZGate { a, b }).into()
+    }
+
+    /// iSWAP gate on `a` and `b`.
+    pub fn iswap(a: usize, b: usize) -> Self {
+        Gates::ISwap(ISwapGate { a, b }).into()
+    }
+
+    /// Phase gate on `target`.
+    pub fn p(target: usize) -> Self {
+        Gates::Phase(PhaseGate { target }).into()
+    }
+
+    /// Inverse phase gate on `target`.
+    pub fn s_dag(target: usize) -> Self {
+        Gates::PhaseDagger(PhaseDaggerGate { target }).into()
+    }
+
+    /// Pauli X gate on `target`.
+    pub fn x(target: usize) -> Self {
+        Gates::PauliX(PauliXGate { target }).into()
+    }
+
+    /// Pauli Y gate on `target`.
+    pub fn y(target: usize) -> Self {
+        Gates::PauliY(PauliYGate { target }).into()
+    }
+
+    /// Pauli Z gate on `target`.
+    pub fn z(target: usize) -> Self {
+        Gates::PauliZ(PauliZGate { target }).into()
+    }
+
+    /// Z-basis measurement of `target`.
+    pub fn measure(target: usize) -> Self {
+        Self::Measure { target }
+    }
+
+    /// This instruction with each of its qubits `q` replaced by `f(q)`.
+    pub fn map_qubits(&self, f: impl Fn(usize) -> usize) -> Self {
+        match self {
+            Self::Gate(gate) => Self::Gate(gate.map_qubits(f)),
+            Self::GateBroadcast(gates) => {
+                Self::GateBroadcast(gates.iter().map(|gate| gate.map_qubits(&f)).collect())
+            }
+            Self::Measure { target } => Self::Measure { target: f(*target) },
+            Self::Detector { .. } => self.clone(),
+        }
+    }
+}
+
+impl From<Gates> for Instruction {
+    fn from(gate: Gates) -> Self {
+        Self::Gate(gate)
+    }
+}
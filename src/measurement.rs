@@ -1,5 +1,34 @@
 /// A measurement of a bit as a fixed or random `1` or `0`.
-#[derive(Clone, Copy, Debug)]
+///
+/// Measurements compare and hash by their full byte, so a fixed `1` is not equal to a random `1`.
+/// Compare with a `bool` to check just the bit.
+///
+/// ```
+/// use circus::Measurement;
+/// use std::{
+///     collections::{hash_map::DefaultHasher, HashMap},
+///     hash::{Hash, Hasher},
+/// };
+///
+/// let (a, b) = (Measurement::fixed(true), Measurement::fixed(true));
+/// assert_eq!(a, b);
+/// let hash = |m: Measurement| {
+///     let mut hasher = DefaultHasher::new();
+///     m.hash(&mut hasher);
+///     hasher.finish()
+/// };
+/// assert_eq!(hash(a), hash(b));
+///
+/// assert_ne!(a, Measurement::random(true));
+/// assert_eq!(Measurement::random(true), true);
+///
+/// let mut histogram = HashMap::new();
+/// for m in [a, b, Measurement::random(false)] {
+///     *histogram.entry(m).or_insert(0) += 1;
+/// }
+/// assert_eq!(histogram[&a], 2);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Measurement {
     byte: u8,
 }
@@ -28,4 +57,188 @@ impl Measurement {
     pub const fn is_random(self) -> bool {
         self.byte >= 2
     }
+
+    /// The measured bit, regardless of whether it was fixed or random.
+    ///
+    /// ```
+    /// use circus::Measurement;
+    ///
+    /// assert!(!Measurement::new(0).bit());
+    /// assert!(Measurement::new(1).bit());
+    /// assert!(!Measurement::new(2).bit());
+    /// assert!(Measurement::new(3).bit());
+    /// ```
+    pub const fn bit(self) -> bool {
+        self.is_one()
+    }
+
+    /// Whether this measurement was fixed or random, and its bit, to match on.
+    ///
+    /// ```
+    /// use circus::{Measurement, Outcome};
+    ///
+    /// let kinds = [
+    ///     Outcome::DeterministicZero,
+    ///     Outcome::DeterministicOne,
+    ///     Outcome::RandomZero,
+    ///     Outcome::RandomOne,
+    /// ];
+    /// for (byte, kind) in kinds.into_iter().enumerate() {
+    ///     assert_eq!(Measurement::new(byte as u8).kind(), kind);
+    /// }
+    /// assert_eq!(Measurement::random(true).kind(), Outcome::RandomOne);
+    /// ```
+    pub const fn kind(self) -> Outcome {
+        match (self.is_random(), self.is_one()) {
+            (false, false) => Outcome::DeterministicZero,
+            (false, true) => Outcome::DeterministicOne,
+            (true, false) => Outcome::RandomZero,
+            (true, true) => Outcome::RandomOne,
+        }
+    }
+
+    /// The raw byte of this measurement:
+    /// `0` or `1` for a fixed bit, and `2` or `3` for a random bit.
+    pub const fn as_u8(self) -> u8 {
+        self.byte
+    }
+}
+
+/// The four kinds of [`Measurement`], returned by [`Measurement::kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// A fixed `0`, byte `0`.
+    DeterministicZero,
+    /// A fixed `1`, byte `1`.
+    DeterministicOne,
+    /// A random `0`, byte `2`.
+    RandomZero,
+    /// A random `1`, byte `3`.
+    RandomOne,
+}
+
+impl From<Measurement> for bool {
+    fn from(measurement: Measurement) -> Self {
+        measurement.bit()
+    }
+}
+
+impl From<Measurement> for u8 {
+    fn from(measurement: Measurement) -> Self {
+        measurement.as_u8()
+    }
+}
+
+/// Compare the measured bit, regardless of whether it was fixed or random.
+///
+/// ```
+/// use circus::Measurement;
+///
+/// for byte in 0..4 {
+///     let m = Measurement::new(byte);
+///     assert_eq!(bool::from(m), byte % 2 == 1);
+///     assert_eq!(u8::from(m), byte);
+///     assert_eq!(m, byte % 2 == 1);
+/// }
+/// ```
+impl PartialEq<bool> for Measurement {
+    fn eq(&self, other: &bool) -> bool {
+        self.bit() == *other
+    }
+}
+
+/// A sequence of measurements packed two bits each into `u64` words,
+/// for storing many shots in a quarter of the memory of a `Vec<Measurement>`.
+///
+/// ```
+/// use circus::{Measurement, MeasurementVec};
+///
+/// let measurements: Vec<_> = (0..200).map(|i| Measurement::new((i * 7 % 4) as u8)).collect();
+/// let packed: MeasurementVec = measurements.iter().copied().collect();
+///
+/// assert_eq!(packed.len(), 200);
+/// for (i, m) in measurements.iter().enumerate() {
+///     assert_eq!(packed.get(i).map(Measurement::as_u8), Some(m.as_u8()));
+/// }
+/// assert!(packed.iter().map(Measurement::as_u8).eq(measurements.iter().map(|m| m.as_u8())));
+/// assert!(packed.get(200).is_none());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MeasurementVec {
+    bits: Vec<u64>,
+    random: Vec<u64>,
+    len: usize,
+}
+
+impl MeasurementVec {
+    /// Create an empty vector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of measurements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no measurements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Append a measurement.
+    pub fn push(&mut self, measurement: Measurement) {
+        let (word, mask) = (self.len / 64, 1 << (self.len % 64));
+        if word == self.bits.len() {
+            self.bits.push(0);
+            self.random.push(0);
+        }
+        if measurement.bit() {
+            self.bits[word] |= mask;
+        }
+        if measurement.is_random() {
+            self.random[word] |= mask;
+        }
+        self.len += 1;
+    }
+
+    /// The measurement at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<Measurement> {
+        if index >= self.len {
+            return None;
+        }
+        let (word, mask) = (index / 64, 1 << (index % 64));
+        let bit = self.bits[word] & mask != 0;
+        Some(if self.random[word] & mask != 0 {
+            Measurement::random(bit)
+        } else {
+            Measurement::fixed(bit)
+        })
+    }
+
+    /// Iterate over the measurements in order.
+    pub fn iter(&self) -> impl Iterator<Item = Measurement> + '_ {
+        (0..self.len).filter_map(|index| self.get(index))
+    }
+
+    /// The measured bits, packed 64 to a word starting from the least significant bit.
+    pub fn bit_words(&self) -> &[u64] {
+        &self.bits
+    }
+}
+
+impl Extend<Measurement> for MeasurementVec {
+    fn extend<I: IntoIterator<Item = Measurement>>(&mut self, iter: I) {
+        for measurement in iter {
+            self.push(measurement);
+        }
+    }
+}
+
+impl FromIterator<Measurement> for MeasurementVec {
+    fn from_iter<I: IntoIterator<Item = Measurement>>(iter: I) -> Self {
+        let mut measurements = Self::new();
+        measurements.extend(iter);
+        measurements
+    }
 }
@@ -0,0 +1,466 @@
+//! Mixed stabilizer states, described by fewer than `n` stabilizer generators.
+
+use num_complex::Complex64;
+
+use crate::{
+    bits::xor_assign,
+    gate::Gates,
+    pauli::{PauliString, PauliStringRef},
+    state::{phase_exponent, CanonicalState, MAX_STATEVECTOR_QUBITS},
+    Error, Measurement, State, Word,
+};
+
+/// A mixed state on `n` qubits: the uniform mixture over the joint `+1` eigenspace of
+/// `rank <= n` independent, commuting stabilizer generators.
+///
+/// A pure [`State`] has `rank == n`, and each generator dropped doubles the dimension of the mixture.
+/// Like a [`State`], its tableau is packed into words of type `W`.
+///
+/// ```
+/// use circus::State;
+///
+/// let mut bell = State::new(2);
+/// bell.h(0);
+/// bell.cx(0, 1);
+///
+/// let mut mixed = bell.into_mixed();
+/// assert_eq!(mixed.rank(), 2);
+///
+/// // Only the identity is left of XX and ZZ after tracing out qubit 1
+/// mixed.trace_out(&[1]);
+/// assert_eq!(mixed.rank(), 0);
+/// assert!(mixed.measure(0).is_random());
+/// assert_eq!(mixed.rank(), 1);
+/// ```
+#[derive(Clone)]
+pub struct MixedState<W: Word = u64> {
+    /// Tableau whose stabilizer rows `n..n + rank` are the generators.
+    /// The other rows are conjugated by gates like the rest but otherwise ignored.
+    tableau: State<W>,
+    rank: usize,
+}
+
+impl MixedState {
+    /// The maximally mixed state on `n` qubits, with no stabilizer generators.
+    ///
+    /// Use [`MixedState::packed`] for a state packed into another word type.
+    pub fn maximally_mixed(n: usize) -> Self {
+        Self::packed(n)
+    }
+}
+
+impl<W: Word> MixedState<W> {
+    /// The maximally mixed state on `n` qubits, packed into words of type `W`.
+    ///
+    /// ```
+    /// use circus::mixed::MixedState;
+    ///
+    /// let mut state = MixedState::<u32>::packed(40);
+    /// state.h(0);
+    /// state.cx(0, 39);
+    /// assert!(state.measure(39).is_random());
+    /// assert!(!state.measure(39).is_random());
+    /// assert_eq!(state.rank(), 1);
+    /// ```
+    pub fn packed(n: usize) -> Self {
+        Self {
+            tableau: State::packed(n),
+            rank: 0,
+        }
+    }
+
+    /// Number of qubits in this state.
+    pub fn num_qubits(&self) -> usize {
+        self.tableau.n
+    }
+
+    /// Number of independent stabilizer generators, which is `n` for a pure state.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Iterate over the stabilizer generators.
+    pub fn iter_stabilizers(&self) -> impl Iterator<Item = PauliStringRef<'_, W>> {
+        let n = self.tableau.n;
+        (n..n + self.rank).map(|i| self.tableau.row(i))
+    }
+
+    /// Copy the stabilizer generators.
+    pub fn stabilizers(&self) -> Vec<PauliString> {
+        self.iter_stabilizers().map(|p| p.to_owned()).collect()
+    }
+
+    /// Reduce the stabilizer generators to a form shared by every set of generators for this state,
+    /// like [`State::canonical`].
+    pub fn canonical(&self) -> CanonicalState {
+        let n = self.tableau.n;
+        self.tableau.canonical_rows(n..n + self.rank)
+    }
+
+    /// Apply a stored gate.
+    pub fn apply_gate(&mut self, gate: &Gates) {
+        self.tableau.apply_gate(gate);
+    }
+
+    /// Apply the controlled-NOT gate.
+    pub fn cx(&mut self, control: usize, target: usize) {
+        self.tableau.cx(control, target);
+    }
+
+    /// Apply the controlled-Z gate.
+    pub fn cz(&mut self, a: usize, b: usize) {
+        self.tableau.cz(a, b);
+    }
+
+    /// Apply the Hadamard gate.
+    pub fn h(&mut self, target: usize) {
+        self.tableau.h(target);
+    }
+
+    /// Apply the S gate.
+    pub fn s(&mut self, target: usize) {
+        self.tableau.s(target);
+    }
+
+    /// Apply the inverse of the S gate.
+    pub fn s_dag(&mut self, target: usize) {
+        self.tableau.s_dag(target);
+    }
+
+    /// Apply the Pauli X gate.
+    pub fn x(&mut self, target: usize) {
+        self.tableau.x(target);
+    }
+
+    /// Apply the Pauli Y gate.
+    pub fn y(&mut self, target: usize) {
+        self.tableau.y(target);
+    }
+
+    /// Apply the Pauli Z gate.
+    pub fn z(&mut self, target: usize) {
+        self.tableau.z(target);
+    }
+
+    /// Measure the `target` qubit.
+    ///
+    /// The outcome is random if `Z_target` anticommutes with a generator, or if it commutes with
+    /// all of them without being in the group they generate, in which case `±Z_target` is added as a new generator.
+    ///
+    /// Random outcomes come from the same generator as the [`State`] this was made from,
+    /// so a mixed state made from a [`State::seeded`] state gives reproducible outcomes.
+    ///
+    /// ```
+    /// use circus::{mixed::MixedState, State};
+    ///
+    /// let mut state = MixedState::maximally_mixed(2);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    ///
+    /// let a = state.measure(0);
+    /// assert!(a.is_random());
+    /// let b = state.measure(1);
+    /// assert!(b.is_random());
+    /// assert_eq!(state.rank(), 2);
+    ///
+    /// for (target, m) in [(0, a), (1, b)] {
+    ///     let again = state.measure(target);
+    ///     assert!(!again.is_random() && again.is_one() == m.is_one());
+    /// }
+    ///
+    /// let run = |seed| {
+    ///     let mut state = State::seeded(16, seed).into_mixed();
+    ///     state.trace_out(&(0..16).collect::<Vec<_>>());
+    ///     (0..16).map(|q| state.measure(q)).collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(run(3), run(3));
+    /// assert!((0..10).any(|seed| run(seed) != run(3)));
+    /// ```
+    pub fn measure(&mut self, target: usize) -> Measurement {
+        let n = self.tableau.n;
+        let (b5, pw) = W::locate(target);
+        let generators = n..n + self.rank;
+
+        if let Some(p) = generators
+            .clone()
+            .find(|&p| self.tableau.x_word(p, b5) & pw != W::ZERO)
+        {
+            for i in generators {
+                if i != p && self.tableau.x_word(i, b5) & pw != W::ZERO {
+                    self.rowmult(i, p);
+                }
+            }
+            let bit = self.tableau.random_bit();
+            self.set_z(p, target, bit);
+            return Measurement::random(bit);
+        }
+
+        match self.product_for_z(target) {
+            Some(r) => Measurement::fixed(r == 2),
+            None => {
+                let bit = self.tableau.random_bit();
+                self.set_z(n + self.rank, target, bit);
+                self.rank += 1;
+                Measurement::random(bit)
+            }
+        }
+    }
+
+    /// Discard the `qubits`, leaving them maximally mixed.
+    ///
+    /// This drops every generator acting on them, after combining generators so that as many as possible don't.
+    /// The remaining generators describe the reduced state of the other qubits.
+    pub fn trace_out(&mut self, qubits: &[usize]) {
+        let n = self.tableau.n;
+        for &j in qubits {
+            let (j5, pw) = W::locate(j);
+            let words = [State::<W>::x_word, State::<W>::z_word];
+            for word in words {
+                let generators = n..n + self.rank;
+                let Some(p) = generators
+                    .clone()
+                    .find(|&i| word(&self.tableau, i, j5) & pw != W::ZERO)
+                else {
+                    continue;
+                };
+                for i in generators {
+                    if i != p && word(&self.tableau, i, j5) & pw != W::ZERO {
+                        self.rowmult(i, p);
+                    }
+                }
+                self.remove(p);
+            }
+        }
+    }
+
+    /// Export the `2^n × 2^n` density matrix of this state as a list of rows,
+    /// using the same basis ordering as [`State::to_statevector`].
+    ///
+    /// This is the sum of the `2^rank` elements of the stabilizer group divided by `2^n`,
+    /// so a pure state gives `|ψ⟩⟨ψ|` and a state with no generators gives `I / 2^n`.
+    /// This returns [`Error::TooManyQubits`] for more than `n_max` qubits
+    /// (or more than [`MAX_STATEVECTOR_QUBITS`]).
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut bell = State::new(2);
+    /// bell.h(0);
+    /// bell.cx(0, 1);
+    ///
+    /// let mut mixed = bell.into_mixed();
+    /// mixed.trace_out(&[1]);
+    /// let rho = mixed.to_density_matrix(2).unwrap();
+    ///
+    /// // Tracing qubit 1 out of the matrix as well leaves I/2 on qubit 0
+    /// for a in 0..2 {
+    ///     for b in 0..2 {
+    ///         let entry = rho[a][b] + rho[a | 2][b | 2];
+    ///         let expected = if a == b { 0.5 } else { 0. };
+    ///         assert!((entry.re - expected).abs() < 1e-12 && entry.im.abs() < 1e-12);
+    ///     }
+    /// }
+    ///
+    /// // Tracing out one qubit of a GHZ state leaves a classical mixture of |00⟩ and |11⟩
+    /// let mut ghz = State::new(3);
+    /// ghz.h(0);
+    /// ghz.cx_fanout(0, &[1, 2]);
+    ///
+    /// let mut mixed = ghz.into_mixed();
+    /// mixed.trace_out(&[2]);
+    /// assert_eq!(mixed.rank(), 1);
+    /// let rho = mixed.to_density_matrix(3).unwrap();
+    /// for (i, row) in rho.iter().enumerate() {
+    ///     for (j, entry) in row.iter().enumerate() {
+    ///         let expected = if i == j && [0, 3, 4, 7].contains(&i) { 0.25 } else { 0. };
+    ///         assert!((entry.re - expected).abs() < 1e-12 && entry.im.abs() < 1e-12);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// A pure state gives the same matrix as [`State::to_density_matrix`]:
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(3);
+    /// state.h(0);
+    /// state.s(0);
+    /// state.cx(0, 1);
+    /// state.h(2);
+    /// state.cz(1, 2);
+    ///
+    /// let expected = state.to_density_matrix(3).unwrap();
+    /// let rho = state.into_mixed().to_density_matrix(3).unwrap();
+    /// for (row, expected) in rho.iter().zip(&expected) {
+    ///     for (entry, expected) in row.iter().zip(expected) {
+    ///         assert!((entry - expected).norm() < 1e-12);
+    ///     }
+    /// }
+    /// ```
+    pub fn to_density_matrix(&self, n_max: usize) -> Result<Vec<Vec<Complex64>>, Error> {
+        let t = &self.tableau;
+        let n = t.n;
+        for max in [n_max, MAX_STATEVECTOR_QUBITS] {
+            if n > max {
+                return Err(Error::TooManyQubits { n, max });
+            }
+        }
+
+        let dim = 1 << n;
+        let mut rho = vec![vec![Complex64::new(0., 0.); dim]; dim];
+        let scale = 0.5f64.powi(n as i32);
+
+        // Walk the group in Gray code order, multiplying in one generator at each step
+        let mut element: (Box<[W]>, i32) = (vec![W::ZERO; 2 * t.words].into(), 0);
+        for k in 0..1usize << self.rank {
+            if k > 0 {
+                let i = n + k.trailing_zeros() as usize;
+                mult(&mut element, &(t.xz[i].into(), t.r[i]));
+            }
+
+            let (mut xs, mut zs) = (0, 0);
+            for j in 0..n {
+                let (j5, pw) = W::locate(j);
+                let w = 2 * j5;
+                xs |= ((element.0[w] & pw != W::ZERO) as usize) << j;
+                zs |= ((element.0[w + 1] & pw != W::ZERO) as usize) << j;
+            }
+
+            // Each Y is iXZ, and Z gives a -1 on each of its qubits set in the column
+            let phase = Complex64::i().powi(element.1 + (xs & zs).count_ones() as i32) * scale;
+            for col in 0..dim {
+                let sign = if (col & zs).count_ones() % 2 == 1 {
+                    -1.
+                } else {
+                    1.
+                };
+                rho[col ^ xs][col] += phase * sign;
+            }
+        }
+
+        Ok(rho)
+    }
+
+    /// Find the sign of `Z_target` in the group generated by the stabilizers, which must all commute with it,
+    /// returning its phase exponent or `None` if it isn't in the group.
+    fn product_for_z(&self, target: usize) -> Option<i32> {
+        let t = &self.tableau;
+        let n = t.n;
+        let mut rows: Vec<(Box<[W]>, i32)> = (n..n + self.rank)
+            .map(|i| (t.xz[i].into(), t.r[i]))
+            .collect();
+
+        // Reduce the generators to row echelon form, combining the ones used for `Z_target` as we go
+        let mut product: (Box<[W]>, i32) = (vec![W::ZERO; 2 * t.words].into(), 0);
+        let mut pivot = 0;
+        for c in 0..2 * n {
+            let j = c / 2;
+            let (j5, bit) = W::locate(j);
+            let w = 2 * j5 + c % 2;
+            let Some(k) = (pivot..rows.len()).find(|&k| rows[k].0[w] & bit != W::ZERO) else {
+                continue;
+            };
+            rows.swap(pivot, k);
+            for k in pivot + 1..rows.len() {
+                if rows[k].0[w] & bit != W::ZERO {
+                    let (row, other) = crate::bits::row_pair(&mut rows, k, pivot);
+                    mult(row, other);
+                }
+            }
+
+            // Z_target has only its z bit set, so the product needs to match it on each pivot column
+            let wanted = c == 2 * target + 1;
+            if (product.0[w] & bit != W::ZERO) != wanted {
+                mult(&mut product, &rows[pivot]);
+            }
+            pivot += 1;
+        }
+
+        let (b5, pw) = W::locate(target);
+        let mut z = vec![W::ZERO; 2 * t.words];
+        z[2 * b5 + 1] = pw;
+        (*product.0 == z[..]).then_some(product.1)
+    }
+
+    /// Left-multiply generator row `i` by row `k`.
+    fn rowmult(&mut self, i: usize, k: usize) {
+        let t = &mut self.tableau;
+        let e = phase_exponent(&t.xz[i], &t.xz[k]);
+        t.r[i] = (e + t.r[i] + t.r[k]).rem_euclid(4);
+        let (row, other) = t.xz.row_pair(i, k);
+        xor_assign(row, other);
+    }
+
+    /// Set row `i` to `Z_target`, with a `-` sign if `bit` is set.
+    fn set_z(&mut self, i: usize, target: usize, bit: bool) {
+        let t = &mut self.tableau;
+        t.xz[i].fill(W::ZERO);
+        let (b5, pw) = W::locate(target);
+        *t.z_word_mut(i, b5) = pw;
+        t.r[i] = 2 * bit as i32;
+    }
+
+    /// Remove generator row `i`, moving the last generator into its place.
+    fn remove(&mut self, i: usize) {
+        let t = &mut self.tableau;
+        let last = t.n + self.rank - 1;
+        if i != last {
+            let (row, other) = t.xz.row_pair(i, last);
+            row.copy_from_slice(other);
+            t.r[i] = t.r[last];
+        }
+        t.xz[last].fill(W::ZERO);
+        t.r[last] = 0;
+        self.rank -= 1;
+    }
+}
+
+/// Left-multiply the Pauli `row` by `other`, both given as interleaved words and a phase exponent.
+fn mult<W: Word>(row: &mut (Box<[W]>, i32), other: &(Box<[W]>, i32)) {
+    let e = phase_exponent(&row.0, &other.0);
+    row.1 = (e + row.1 + other.1).rem_euclid(4);
+    xor_assign(&mut row.0, &other.0);
+}
+
+/// Compare the groups generated by the stabilizers of two states, through their [canonical](MixedState::canonical) forms.
+///
+/// Unlike comparing [`State`]s, this ignores the destabilizer rows and the order of the generators.
+///
+/// ```
+/// use circus::State;
+///
+/// let mut a = State::new(2);
+/// a.h(0);
+/// a.cx(0, 1);
+///
+/// let mut b = State::new(2);
+/// b.h(1);
+/// b.cx(1, 0);
+///
+/// assert!(a != b);
+/// assert!(a.clone().into_mixed() == b.clone().into_mixed());
+///
+/// let (mut a, mut b) = (a.into_mixed(), b.into_mixed());
+/// a.trace_out(&[1]);
+/// b.trace_out(&[0]);
+/// assert!(a == b);
+/// assert!(a == circus::mixed::MixedState::maximally_mixed(2));
+/// assert!(a != circus::mixed::MixedState::maximally_mixed(3));
+/// ```
+impl<W: Word> PartialEq for MixedState<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tableau.n == other.tableau.n
+            && self.rank == other.rank
+            && self.canonical() == other.canonical()
+    }
+}
+
+impl<W: Word> Eq for MixedState<W> {}
+
+impl<W: Word> From<State<W>> for MixedState<W> {
+    fn from(tableau: State<W>) -> Self {
+        let rank = tableau.n;
+        Self { tableau, rank }
+    }
+}
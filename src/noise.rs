@@ -0,0 +1,177 @@
+//! Noise injected between the instructions of a circuit.
+
+use crate::{gate::Gates, Instruction};
+use rand::{Rng, RngCore};
+use std::collections::VecDeque;
+
+/// A source of noise instructions, such as random Pauli errors, added to a circuit by [`with_noise`].
+pub trait NoiseModel {
+    /// Noise instructions to run after `gate`.
+    fn after_gate(&self, gate: &Gates, rng: &mut dyn RngCore) -> Vec<Instruction>;
+
+    /// Noise instructions to run before measuring `target`, such as a bit flip modelling readout error.
+    ///
+    /// By default there are none.
+    fn before_measure(&self, target: usize, rng: &mut dyn RngCore) -> Vec<Instruction> {
+        let _ = (target, rng);
+        Vec::new()
+    }
+}
+
+/// Depolarizing noise after each gate: with probability `one_qubit_p` a single-qubit gate is followed
+/// by a uniformly random `X`, `Y` or `Z`, and with probability `two_qubit_p` a two-qubit gate is followed
+/// by one of the 15 non-identity two-qubit Paulis.
+///
+/// ```
+/// use circus::{noise::{self, DepolarizingModel}, prep, Instruction};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let model = DepolarizingModel {
+///     one_qubit_p: 0.,
+///     two_qubit_p: 1.,
+/// };
+/// let mut rng = StdRng::seed_from_u64(0);
+///
+/// // GHZ is a Hadamard followed by two CNOTs
+/// let noisy: Vec<_> = noise::with_noise(prep::ghz(3), &model, &mut rng).collect();
+/// let cx = noisy.iter().position(|i| *i == Instruction::cx(0, 1)).unwrap();
+/// assert_eq!(cx, 1);
+///
+/// // Every instruction after the Hadamard and before the next CNOT is noise on qubits 0 and 1
+/// let next = noisy.iter().position(|i| *i == Instruction::cx(1, 2)).unwrap();
+/// assert!(next > 2);
+/// for noise in &noisy[2..next] {
+///     let Instruction::Gate(gate) = noise else { panic!() };
+///     assert!(gate.qubits().all(|q| q < 2));
+/// }
+/// assert!(noisy.len() > next + 1);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DepolarizingModel {
+    /// Probability of an error after a single-qubit gate.
+    pub one_qubit_p: f64,
+    /// Probability of an error after a two-qubit gate.
+    pub two_qubit_p: f64,
+}
+
+impl NoiseModel for DepolarizingModel {
+    fn after_gate(&self, gate: &Gates, rng: &mut dyn RngCore) -> Vec<Instruction> {
+        let qubits: Vec<_> = gate.qubits().collect();
+        let p = match qubits.len() {
+            1 => self.one_qubit_p,
+            _ => self.two_qubit_p,
+        };
+        if !rng.gen_bool(p) {
+            return Vec::new();
+        }
+
+        // Pick a non-identity Pauli on the gate's qubits, two bits per qubit
+        let paulis = rng.gen_range(1..1 << (2 * qubits.len()));
+        qubits
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &target)| match (paulis >> (2 * i)) & 3 {
+                1 => Some(Instruction::x(target)),
+                2 => Some(Instruction::y(target)),
+                3 => Some(Instruction::z(target)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Run `instructions` with noise from `model`, yielding each instruction followed by the
+/// noise [`NoiseModel::after_gate`] generates for each of its gates.
+/// Each measurement is preceded by the noise from [`NoiseModel::before_measure`].
+///
+/// The result can be passed straight to [`State::run`](crate::State::run).
+///
+/// ```
+/// use circus::{gate::Gates, noise::{self, NoiseModel}, prep, Instruction, State};
+/// use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+///
+/// /// Flip each qubit a gate acts on with probability `p`.
+/// struct BitFlip(f64);
+///
+/// impl NoiseModel for BitFlip {
+///     fn after_gate(&self, gate: &Gates, rng: &mut dyn RngCore) -> Vec<Instruction> {
+///         gate.qubits()
+///             .filter(|_| rng.gen_bool(self.0))
+///             .map(Instruction::x)
+///             .collect()
+///     }
+/// }
+///
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let circuit = prep::ghz(3);
+/// let noisy: Vec<_> = noise::with_noise(circuit.clone(), &BitFlip(0.), &mut rng).collect();
+/// assert_eq!(noisy, circuit);
+///
+/// let noisy: Vec<_> = noise::with_noise(circuit.clone(), &BitFlip(1.), &mut rng).collect();
+/// assert_eq!(noisy.len(), circuit.len() + 5);
+/// assert_eq!(noisy[1], Instruction::x(0));
+///
+/// let mut state = State::new(3);
+/// state.run(noise::with_noise(circuit, &BitFlip(0.1), &mut rng)).for_each(drop);
+/// ```
+pub fn with_noise<'a, I, M, R>(
+    instructions: I,
+    model: &'a M,
+    rng: &'a mut R,
+) -> WithNoise<'a, I::IntoIter, M, R>
+where
+    I: IntoIterator<Item = Instruction>,
+    M: NoiseModel + ?Sized,
+    R: RngCore,
+{
+    WithNoise {
+        instructions: instructions.into_iter(),
+        model,
+        rng,
+        pending: VecDeque::new(),
+    }
+}
+
+/// Iterator over a circuit with noise added, returned by [`with_noise`].
+pub struct WithNoise<'a, I, M: ?Sized, R> {
+    instructions: I,
+    model: &'a M,
+    rng: &'a mut R,
+    pending: VecDeque<Instruction>,
+}
+
+impl<I, M, R> Iterator for WithNoise<'_, I, M, R>
+where
+    I: Iterator<Item = Instruction>,
+    M: NoiseModel + ?Sized,
+    R: RngCore,
+{
+    type Item = Instruction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(instruction) = self.pending.pop_front() {
+            return Some(instruction);
+        }
+
+        let instruction = self.instructions.next()?;
+        let gates = match &instruction {
+            Instruction::Gate(gate) => core::slice::from_ref(gate),
+            Instruction::GateBroadcast(gates) => gates,
+            Instruction::Measure { target } => {
+                let noise = self.model.before_measure(*target, &mut *self.rng);
+                if noise.is_empty() {
+                    return Some(instruction);
+                }
+                self.pending.extend(noise);
+                self.pending.push_back(instruction);
+                return self.pending.pop_front();
+            }
+            Instruction::Detector { .. } => &[],
+        };
+        for gate in gates {
+            let noise = self.model.after_gate(gate, &mut *self.rng);
+            self.pending.extend(noise);
+        }
+        Some(instruction)
+    }
+}
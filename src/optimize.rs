@@ -0,0 +1,167 @@
+//! Circuit optimization passes.
+
+use crate::{
+    clifford::Clifford,
+    gate::{Gates, HadamardGate, PauliXGate, PauliYGate, PauliZGate, PhaseDaggerGate, PhaseGate},
+    Instruction,
+};
+use std::collections::BTreeMap;
+
+/// Remove adjacent pairs of gates that cancel, such as `H·H`, `X·X`, `CX·CX` or `S·S†`.
+///
+/// Gates only cancel when no other instruction acts on any of their qubits in between,
+/// so measurements and broadcast gate groups block cancellation on the qubits they touch.
+/// Removing a pair can expose another, so `[H0, X0, X0, H0]` cancels completely.
+///
+/// ```
+/// use circus::{
+///     gate::{Gates, HadamardGate, PauliXGate},
+///     optimize, Instruction,
+/// };
+///
+/// let h0 = Instruction::Gate(Gates::Hadamard(HadamardGate { target: 0 }));
+/// let x1 = Instruction::Gate(Gates::PauliX(PauliXGate { target: 1 }));
+/// let m0 = Instruction::Measure { target: 0 };
+///
+/// let circuit = [h0.clone(), h0.clone(), x1.clone()];
+/// assert_eq!(optimize::cancel_inverses(&circuit), [x1]);
+///
+/// let circuit = [h0.clone(), m0, h0];
+/// assert_eq!(optimize::cancel_inverses(&circuit), circuit);
+/// ```
+pub fn cancel_inverses(instructions: &[Instruction]) -> Vec<Instruction> {
+    let mut optimized: Vec<Instruction> = Vec::with_capacity(instructions.len());
+
+    for instruction in instructions {
+        if let Instruction::Gate(gate) = instruction {
+            // Find the last instruction acting on any of this gate's qubits
+            let last = optimized
+                .iter()
+                .rposition(|other| gate.qubits().any(|qubit| touches(other, qubit)));
+
+            if let (Some(idx), Some(inverse)) = (last, gate.inverse()) {
+                if optimized[idx] == Instruction::Gate(inverse) {
+                    optimized.remove(idx);
+                    continue;
+                }
+            }
+        }
+
+        optimized.push(instruction.clone());
+    }
+
+    optimized
+}
+
+/// Fuse each run of single-qubit gates on a qubit into the shortest equivalent sequence
+/// of `H`, `S`, `S†`, `X`, `Y` and `Z`, up to a global phase.
+///
+/// A run ends at the next instruction acting on the qubit that isn't a single-qubit gate,
+/// such as a two-qubit gate, a broadcast gate group or a measurement, where its fused gates are placed.
+/// Every one of the 24 single-qubit Cliffords needs at most three gates.
+///
+/// ```
+/// use circus::{equiv, optimize, Instruction};
+///
+/// let h = Instruction::h(0);
+/// assert_eq!(optimize::fuse_single_qubit(&[h.clone(), h.clone()]), []);
+///
+/// let s = Instruction::p(0);
+/// assert_eq!(optimize::fuse_single_qubit(&[s.clone(), s.clone()]), [Instruction::z(0)]);
+///
+/// let circuit = [
+///     Instruction::h(0),
+///     Instruction::x(1),
+///     Instruction::p(0),
+///     Instruction::h(0),
+///     Instruction::p(0),
+///     Instruction::cx(0, 1),
+///     Instruction::z(1),
+///     Instruction::y(1),
+///     Instruction::x(1),
+/// ];
+/// let fused = optimize::fuse_single_qubit(&circuit);
+/// assert!(fused.len() < circuit.len());
+/// assert_eq!(equiv::are_equivalent(2, &circuit, &fused), Ok(true));
+/// ```
+pub fn fuse_single_qubit(instructions: &[Instruction]) -> Vec<Instruction> {
+    let table = shortest_single_qubit_cliffords();
+    let mut optimized = Vec::with_capacity(instructions.len());
+    let mut runs: BTreeMap<usize, Vec<Gates>> = BTreeMap::new();
+
+    let flush = |optimized: &mut Vec<Instruction>, run: Vec<Gates>, qubit: usize| {
+        let clifford = Clifford::from_gates(1, &run);
+        let (_, gates) = table.iter().find(|(c, _)| *c == clifford).unwrap();
+        optimized.extend(
+            gates
+                .iter()
+                .map(|gate| Instruction::Gate(gate.map_qubits(|_| qubit))),
+        );
+    };
+
+    for instruction in instructions {
+        if let Instruction::Gate(gate) = instruction {
+            let mut qubits = gate.qubits();
+            if let (Some(qubit), None) = (qubits.next(), qubits.next()) {
+                runs.entry(qubit).or_default().push(gate.map_qubits(|_| 0));
+                continue;
+            }
+        }
+
+        let blocked: Vec<_> = runs
+            .keys()
+            .copied()
+            .filter(|&qubit| touches(instruction, qubit))
+            .collect();
+        for qubit in blocked {
+            let run = runs.remove(&qubit).unwrap();
+            flush(&mut optimized, run, qubit);
+        }
+        optimized.push(instruction.clone());
+    }
+
+    for (qubit, run) in runs {
+        flush(&mut optimized, run, qubit);
+    }
+    optimized
+}
+
+/// Each of the 24 single-qubit Cliffords on qubit 0 with a shortest sequence of gates implementing it,
+/// found by a breadth-first search.
+fn shortest_single_qubit_cliffords() -> Vec<(Clifford, Vec<Gates>)> {
+    let target = 0;
+    let generators = [
+        Gates::Hadamard(HadamardGate { target }),
+        Gates::Phase(PhaseGate { target }),
+        Gates::PhaseDagger(PhaseDaggerGate { target }),
+        Gates::PauliX(PauliXGate { target }),
+        Gates::PauliY(PauliYGate { target }),
+        Gates::PauliZ(PauliZGate { target }),
+    ];
+
+    let mut table = vec![(Clifford::identity(1), Vec::new())];
+    let mut next = 0;
+    while next < table.len() {
+        for gate in generators {
+            let mut gates = table[next].1.clone();
+            gates.push(gate);
+            let clifford = Clifford::from_gates(1, &gates);
+            if table.iter().all(|(c, _)| *c != clifford) {
+                table.push((clifford, gates));
+            }
+        }
+        next += 1;
+    }
+    table
+}
+
+fn touches(instruction: &Instruction, qubit: usize) -> bool {
+    match instruction {
+        Instruction::Gate(gate) => gate.qubits().any(|q| q == qubit),
+        Instruction::GateBroadcast(gates) => {
+            gates.iter().any(|gate| gate.qubits().any(|q| q == qubit))
+        }
+        Instruction::Measure { target } => *target == qubit,
+        Instruction::Detector { .. } => false,
+    }
+}
@@ -0,0 +1,281 @@
+//! Pauli operators and strings of them.
+
+use crate::{bits::repack_row, Word};
+use core::{fmt, str::FromStr};
+
+/// Single-qubit Pauli operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pauli {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+impl Pauli {
+    /// Create a Pauli from its x and z bits (`Y` has both set).
+    pub const fn from_bits(x: bool, z: bool) -> Self {
+        match (x, z) {
+            (false, false) => Self::I,
+            (true, false) => Self::X,
+            (true, true) => Self::Y,
+            (false, true) => Self::Z,
+        }
+    }
+
+    /// The x and z bits of this Pauli.
+    pub const fn bits(self) -> (bool, bool) {
+        match self {
+            Self::I => (false, false),
+            Self::X => (true, false),
+            Self::Y => (true, true),
+            Self::Z => (false, true),
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Self::I => 'I',
+            Self::X => 'X',
+            Self::Y => 'Y',
+            Self::Z => 'Z',
+        }
+    }
+}
+
+/// Basis of a single-qubit measurement, named by the Pauli operator measured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PauliBasis {
+    X,
+    Y,
+    Z,
+}
+
+impl From<PauliBasis> for Pauli {
+    fn from(basis: PauliBasis) -> Self {
+        match basis {
+            PauliBasis::X => Self::X,
+            PauliBasis::Y => Self::Y,
+            PauliBasis::Z => Self::Z,
+        }
+    }
+}
+
+/// Multi-qubit Pauli operator with a phase of `i^r`,
+/// stored as interleaved x and z words in the same layout as the rows of a [`State`](crate::State)
+/// with its default `u64` words.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PauliString {
+    n: usize,
+    xz: Box<[u64]>,
+    r: i32,
+}
+
+impl PauliString {
+    /// Create the identity on `n` qubits.
+    pub fn new(n: usize) -> Self {
+        let words = n / u64::WIDTH + 1;
+        Self {
+            n,
+            xz: vec![0; 2 * words].into_boxed_slice(),
+            r: 0,
+        }
+    }
+
+    /// Number of qubits this operator acts on.
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Returns `true` if this operator acts on no qubits.
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// The Pauli acting on qubit `j`.
+    pub fn get(&self, j: usize) -> Pauli {
+        self.as_ref().get(j)
+    }
+
+    /// Set the Pauli acting on qubit `j`.
+    pub fn set(&mut self, j: usize, pauli: Pauli) {
+        let (j5, pw) = u64::locate(j);
+        let (x, z) = pauli.bits();
+        let (xj, zj) = (2 * j5, 2 * j5 + 1);
+        self.xz[xj] = if x {
+            self.xz[xj] | pw
+        } else {
+            self.xz[xj] & !pw
+        };
+        self.xz[zj] = if z {
+            self.xz[zj] | pw
+        } else {
+            self.xz[zj] & !pw
+        };
+    }
+
+    /// Phase exponent `r`, where this operator's phase is `i^r`
+    /// (0 for +1, 1 for i, 2 for -1, 3 for -i).
+    pub fn phase(&self) -> i32 {
+        self.r
+    }
+
+    /// Set the phase exponent `r` (taken mod 4).
+    pub fn set_phase(&mut self, r: i32) {
+        self.r = r.rem_euclid(4);
+    }
+
+    /// Borrow this operator as a [`PauliStringRef`].
+    pub fn as_ref(&self) -> PauliStringRef<'_> {
+        PauliStringRef {
+            n: self.n,
+            xz: &self.xz,
+            r: self.r,
+        }
+    }
+
+    /// Copy the interleaved x and z words of this operator into words of type `W`.
+    pub(crate) fn words<W: Word>(&self) -> Box<[W]> {
+        let mut xz = vec![W::ZERO; 2 * (self.n / W::WIDTH + 1)];
+        repack_row(self.n, &self.xz, &mut xz);
+        xz.into_boxed_slice()
+    }
+}
+
+impl fmt::Display for PauliString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+/// Parse a Pauli string such as `+XZI`, `-ZZ` or `iY`, where `_` is also read as `I`.
+///
+/// ```
+/// use circus::pauli::{Pauli, PauliString};
+///
+/// let p: PauliString = "-XZ_".parse().unwrap();
+/// assert_eq!(p.len(), 3);
+/// assert_eq!(p.phase(), 2);
+/// assert_eq!(p.get(1), Pauli::Z);
+/// assert_eq!(p.to_string(), "-XZI");
+/// ```
+impl FromStr for PauliString {
+    type Err = ParsePauliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (r, paulis) = if let Some(rest) = s.strip_prefix("+i").or(s.strip_prefix('i')) {
+            (1, rest)
+        } else if let Some(rest) = s.strip_prefix("-i") {
+            (3, rest)
+        } else if let Some(rest) = s.strip_prefix('-') {
+            (2, rest)
+        } else {
+            (0, s.strip_prefix('+').unwrap_or(s))
+        };
+
+        let mut pauli_string = Self::new(paulis.chars().count());
+        pauli_string.r = r;
+        for (j, c) in paulis.chars().enumerate() {
+            let pauli = match c {
+                'I' | '_' => Pauli::I,
+                'X' => Pauli::X,
+                'Y' => Pauli::Y,
+                'Z' => Pauli::Z,
+                c => return Err(ParsePauliError { found: c }),
+            };
+            pauli_string.set(j, pauli);
+        }
+        Ok(pauli_string)
+    }
+}
+
+/// Error parsing a [`PauliString`] from a string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParsePauliError {
+    /// The unexpected character.
+    pub found: char,
+}
+
+impl fmt::Display for ParsePauliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid Pauli `{}`", self.found)
+    }
+}
+
+impl std::error::Error for ParsePauliError {}
+
+/// Borrowed view of a Pauli operator, such as a row of a [`State`](crate::State)'s tableau
+/// packed into words of type `W`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PauliStringRef<'a, W: Word = u64> {
+    pub(crate) n: usize,
+    /// Interleaved x and z words.
+    pub(crate) xz: &'a [W],
+    pub(crate) r: i32,
+}
+
+impl<W: Word> PauliStringRef<'_, W> {
+    /// Number of qubits this operator acts on.
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Returns `true` if this operator acts on no qubits.
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// The Pauli acting on qubit `j`.
+    pub fn get(&self, j: usize) -> Pauli {
+        let (j5, pw) = W::locate(j);
+        Pauli::from_bits(
+            self.xz[2 * j5] & pw != W::ZERO,
+            self.xz[2 * j5 + 1] & pw != W::ZERO,
+        )
+    }
+
+    /// Phase exponent `r`, where this operator's phase is `i^r`.
+    pub fn phase(&self) -> i32 {
+        self.r
+    }
+
+    /// Write the sign followed by each non-identity Pauli and its qubit, such as `+X0 Z3`.
+    pub(crate) fn write_sparse(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(self.sign())?;
+        let mut paulis = (0..self.n).filter(|&j| self.get(j) != Pauli::I);
+        if let Some(j) = paulis.next() {
+            write!(f, "{}{j}", self.get(j).to_char())?;
+        }
+        for j in paulis {
+            write!(f, " {}{j}", self.get(j).to_char())?;
+        }
+        Ok(())
+    }
+
+    fn sign(&self) -> &'static str {
+        match self.r {
+            0 => "+",
+            1 => "+i",
+            2 => "-",
+            _ => "-i",
+        }
+    }
+
+    /// Copy this view into an owned [`PauliString`], repacking it into `u64` words.
+    pub fn to_owned(&self) -> PauliString {
+        let mut pauli_string = PauliString::new(self.n);
+        repack_row(self.n, self.xz, &mut pauli_string.xz);
+        pauli_string.r = self.r;
+        pauli_string
+    }
+}
+
+impl<W: Word> fmt::Display for PauliStringRef<'_, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.sign())?;
+        for j in 0..self.n {
+            write!(f, "{}", self.get(j).to_char())?;
+        }
+        Ok(())
+    }
+}
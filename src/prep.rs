@@ -0,0 +1,54 @@
+//! Circuits preparing common entangled states.
+
+use crate::{
+    gate::{CNotGate, CZGate, Gates, HadamardGate},
+    Instruction,
+};
+
+/// Prepare the `n` qubit GHZ state `(|0...0⟩ + |1...1⟩) / √2`
+/// with a Hadamard on qubit 0 followed by a chain of CNOTs.
+///
+/// ```
+/// use circus::{prep, Instruction, State};
+///
+/// let mut circuit = prep::ghz(3);
+/// circuit.extend((0..3).map(|target| Instruction::Measure { target }));
+///
+/// let mut state = State::new(3);
+/// let m: Vec<_> = state.run(circuit).collect();
+/// assert!(m.iter().all(|bit| bit.is_one() == m[0].is_one()));
+/// ```
+pub fn ghz(n: usize) -> Vec<Instruction> {
+    let mut instructions = Vec::with_capacity(n);
+    if n > 0 {
+        instructions.push(Instruction::Gate(Gates::Hadamard(HadamardGate {
+            target: 0,
+        })));
+    }
+    for target in 1..n {
+        instructions.push(Instruction::Gate(Gates::CNot(CNotGate {
+            control: target - 1,
+            target,
+        })));
+    }
+    instructions
+}
+
+/// Prepare the `n` qubit graph state with the given `edges`
+/// with a Hadamard on every qubit followed by a CZ for each edge.
+///
+/// Each qubit `a` is then stabilized by `X_a` times `Z_b` for each neighbour `b`.
+///
+/// ```
+/// use circus::{prep, State};
+///
+/// let mut state = State::new(3);
+/// state.run(prep::graph_state(&[(0, 1), (1, 2)], 3)).for_each(drop);
+///
+/// assert!(state.to_string().ends_with("+XZI\n+ZXZ\n+IZX\n"));
+/// ```
+pub fn graph_state(edges: &[(usize, usize)], n: usize) -> Vec<Instruction> {
+    let hadamards = (0..n).map(|target| Gates::Hadamard(HadamardGate { target }));
+    let czs = edges.iter().map(|&(a, b)| Gates::CZ(CZGate { a, b }));
+    hadamards.chain(czs).map(Instruction::Gate).collect()
+}
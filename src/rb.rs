@@ -0,0 +1,67 @@
+//! Randomized benchmarking.
+
+use crate::{
+    clifford::{Clifford, SingleQubitClifford},
+    gate::{CNotGate, Gates},
+    Instruction,
+};
+use rand::{seq::index, Rng};
+
+/// Generate a randomized benchmarking sequence of `length` random Cliffords on `n` qubits,
+/// followed by the Clifford inverting all of them, so the ideal circuit returns `|0...0⟩` to itself.
+///
+/// Each Clifford is a single [`Instruction::GateBroadcast`] of a random [`SingleQubitClifford`]
+/// on every qubit, then a CNOT between a random pair of qubits (for more than one qubit).
+/// These are not sampled uniformly from the `n` qubit Clifford group.
+/// The inversion is a single broadcast synthesized by [`Clifford::to_circuit`]
+/// from the [`Clifford::inverse`] of the whole sequence.
+///
+/// ```
+/// use circus::{rb, Instruction, State};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let mut circuit = rb::rb_sequence(3, 20, &mut rng);
+/// assert_eq!(circuit.len(), 21);
+///
+/// circuit.extend((0..3).map(|target| Instruction::Measure { target }));
+/// let mut state = State::new(3);
+/// for m in state.run(circuit) {
+///     assert!(m.is_zero() && !m.is_random());
+/// }
+/// ```
+pub fn rb_sequence<R: Rng + ?Sized>(n: usize, length: usize, rng: &mut R) -> Vec<Instruction> {
+    let mut sequence = Vec::with_capacity(length + 1);
+
+    for _ in 0..length {
+        let mut gates = Vec::new();
+        for target in 0..n {
+            gates.extend(SingleQubitClifford::random(rng).gates(target));
+        }
+
+        if n > 1 {
+            let pair = index::sample(rng, n, 2);
+            let cx = Gates::CNot(CNotGate {
+                control: pair.index(0),
+                target: pair.index(1),
+            });
+            gates.push(cx);
+        }
+
+        sequence.push(Instruction::GateBroadcast(gates));
+    }
+
+    // Every gate acts on one of the n qubits, so this can't fail validation
+    let clifford = Clifford::from_instructions(n, &sequence).unwrap();
+    let inverse = clifford
+        .inverse()
+        .to_circuit()
+        .into_iter()
+        .filter_map(|instruction| match instruction {
+            Instruction::Gate(gate) => Some(gate),
+            _ => None,
+        })
+        .collect();
+    sequence.push(Instruction::GateBroadcast(inverse));
+    sequence
+}
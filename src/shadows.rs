@@ -0,0 +1,54 @@
+//! Classical shadows, which estimate properties of a state from measurements in random bases.
+
+use crate::{clifford::SingleQubitClifford, State};
+use rand::Rng;
+
+/// Take one classical shadow snapshot of `state`: apply a uniformly random single-qubit Clifford
+/// to each qubit, measure every qubit in the Z basis, and return each qubit's Clifford and outcome.
+///
+/// This collapses `state`, so clone it first to take more snapshots.
+///
+/// ```
+/// use circus::{shadows, State};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let mut input = State::new(3);
+/// input.x(1);
+///
+/// for _ in 0..20 {
+///     let snapshot = shadows::single_shot(&mut input.clone(), &mut rng);
+///     assert_eq!(snapshot.len(), 3);
+///
+///     // Each outcome must be possible after applying its Clifford to the input qubit
+///     for (target, (clifford, outcome)) in snapshot.into_iter().enumerate() {
+///         let mut qubit = State::from_bits(&[target == 1]);
+///         for gate in clifford.gates(0) {
+///             qubit.apply_gate(&gate);
+///         }
+///         let m = qubit.measure(0);
+///         assert!(m.is_random() || m.bit() == outcome);
+///     }
+/// }
+/// ```
+pub fn single_shot<R: Rng + ?Sized>(
+    state: &mut State,
+    rng: &mut R,
+) -> Vec<(SingleQubitClifford, bool)> {
+    let cliffords: Vec<_> = (0..state.n)
+        .map(|target| {
+            let clifford = SingleQubitClifford::random(rng);
+            for gate in clifford.gates(target) {
+                state.apply_gate(&gate);
+            }
+            clifford
+        })
+        .collect();
+
+    let outcomes = state.measure_all();
+    cliffords
+        .into_iter()
+        .zip(outcomes)
+        .map(|(clifford, outcome)| (clifford, outcome.bit()))
+        .collect()
+}
@@ -1,163 +1,2210 @@
-use core::fmt;
+use core::{
+    fmt,
+    ops::{Index, IndexMut, Range},
+};
+use num_complex::Complex64;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    gate::{CNotGate, Gate, HadamardGate, PhaseGate},
-    Instruction, Measurement, PW,
+    bits::{repack_row, row_pair, xor_assign},
+    clifford::{Clifford, SymplecticMatrix},
+    clifford_t,
+    gate::{
+        cz_row, CNotGate, CZGate, Gate, Gates, HadamardGate, ISwapGate, PauliXGate, PauliYGate,
+        PauliZGate, PhaseDaggerGate, PhaseGate,
+    },
+    mixed::MixedState,
+    pauli::{Pauli, PauliBasis, PauliString, PauliStringRef},
+    stim::{self, StimError},
+    Error, Instruction, Measurement, Word,
 };
 
-pub type BinaryMatrix = Box<[Box<[u64]>]>;
+/// Maximum number of qubits for [`State::to_statevector`].
+pub const MAX_STATEVECTOR_QUBITS: usize = 20;
+
+/// Maximum value of [`State::nonzero`] for [`State::ket`], which lists all `2^g` nonzero basis states.
+///
+/// At this limit the string is 65536 lines long, which is already more than anyone can read.
+pub const MAX_KET_NONZERO: usize = 16;
+
+/// Maximum value of [`State::nonzero`] for [`State::ket_pretty`], which writes all `2^g` terms on a single line.
+pub const MAX_KET_PRETTY_NONZERO: usize = 10;
+
+/// Bit matrix stored as one contiguous buffer of words, row by row.
+///
+/// Indexing with a row number returns that row's words.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinaryMatrix<W: Word = u64> {
+    words: usize,
+    data: Box<[W]>,
+}
+
+impl<W: Word> BinaryMatrix<W> {
+    /// Create a zeroed matrix of `rows` rows of `words` words each.
+    pub fn new(rows: usize, words: usize) -> Self {
+        Self {
+            words,
+            data: vec![W::ZERO; rows * words].into_boxed_slice(),
+        }
+    }
+
+    /// Number of rows in this matrix.
+    pub fn rows(&self) -> usize {
+        self.data.len().checked_div(self.words).unwrap_or(0)
+    }
 
-/// Create a binary matrix for an `n` number of qubits.
-pub fn binary_matrix(n: usize) -> BinaryMatrix {
-    let len = 2 * n + 1;
-    let over32 = (n >> 5) + 1;
+    /// Number of words in each row.
+    pub fn words(&self) -> usize {
+        self.words
+    }
 
-    (0..len)
-        .map(|_| vec![0; over32].into_boxed_slice())
-        .collect::<Vec<_>>()
-        .into_boxed_slice()
+    /// Borrow row `i` mutably and row `k` immutably, where `i != k`.
+    pub(crate) fn row_pair(&mut self, i: usize, k: usize) -> (&mut [W], &[W]) {
+        let w = self.words;
+        if i < k {
+            let (head, tail) = self.data.split_at_mut(k * w);
+            (&mut head[i * w..(i + 1) * w], &tail[..w])
+        } else {
+            let (head, tail) = self.data.split_at_mut(i * w);
+            (&mut tail[..w], &head[k * w..(k + 1) * w])
+        }
+    }
+}
+
+impl<W: Word> Index<usize> for BinaryMatrix<W> {
+    type Output = [W];
+
+    fn index(&self, row: usize) -> &[W] {
+        &self.data[row * self.words..(row + 1) * self.words]
+    }
+}
+
+impl<W: Word> IndexMut<usize> for BinaryMatrix<W> {
+    fn index_mut(&mut self, row: usize) -> &mut [W] {
+        &mut self.data[row * self.words..(row + 1) * self.words]
+    }
+}
+
+/// Create a binary matrix for an `n` number of qubits,
+/// with the x and z words of each row interleaved as `[x0, z0, x1, z1, ...]`.
+pub fn binary_matrix<W: Word>(n: usize) -> BinaryMatrix<W> {
+    BinaryMatrix::new(2 * n + 1, 2 * (n / W::WIDTH + 1))
 }
 
 /// Quantum stabilizer state (from [Improved Simulation of Stabilizer Circuits](https://arxiv.org/abs/quant-ph/0406196)
 /// by Scott Aaronson and Daniel Gottesman)
-pub struct State {
+///
+/// The bits of each row are packed into words of type `W`, [`Word::WIDTH`] qubits to a word.
+#[derive(Clone)]
+pub struct State<W: Word = u64> {
     /// Number of qubits.
     pub n: usize,
 
-    /// floor(n/8)+1
-    pub over32: usize,
+    /// Number of words holding the x bits of each row, and again its z bits: floor(n / W::WIDTH) + 1.
+    pub words: usize,
 
-    /// (2n+1)*n matrix for stabilizer/destabilizer x bits.
-    pub x: BinaryMatrix,
-
-    /// (2n+1)*n matrix for stabilizer/destabilizer z bits.
-    pub z: BinaryMatrix,
+    /// (2n+1)*2n matrix for stabilizer/destabilizer x and z bits, with their words interleaved.
+    pub(crate) xz: BinaryMatrix<W>,
 
     /// Phase bits (0 for +1, 1 for i, 2 for -1, 3 for -i). Normally either 0 or 2.
     pub r: Box<[i32]>,
+
+    /// Source of measurement outcomes for [`State::seeded`], or the thread-local RNG if `None`.
+    rng: Option<StdRng>,
+
+    #[cfg(feature = "op-stats")]
+    op_stats: OpStats,
 }
 
+/// Number of row operations performed on a [`State`]'s tableau.
+#[cfg(feature = "op-stats")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OpStats {
+    /// Row multiplications, each of which also computes a phase with `clifford`.
+    pub rowmult: u64,
+    /// Row copies, including the three performed by each row swap.
+    pub rowcopy: u64,
+    /// Row swaps.
+    pub rowswap: u64,
+    /// Phase computations for row products.
+    pub clifford: u64,
+}
+
+/// [`State`] with its default word type, packing 64 qubits into each word.
+pub type DefaultState = State<u64>;
+
 impl State {
     /// Create a quantum state with `n` number of qubits.
+    ///
+    /// Use [`State::packed`] for a state packed into another word type.
     pub fn new(n: usize) -> Self {
-        let len = 2 * n + 1;
-        let over32 = (n >> 5) + 1;
-        let mut x = binary_matrix(n);
-        let mut z = binary_matrix(n);
-        let r = vec![0; len].into_boxed_slice();
+        Self::packed(n)
+    }
+
+    /// Create a quantum state with `n` qubits whose random measurement outcomes are drawn
+    /// from an RNG seeded with `seed`, so that runs are reproducible.
+    ///
+    /// Use [`State::packed_seeded`] for a state packed into another word type.
+    ///
+    /// Clones share the RNG state at the time they were made, so they repeat each other's outcomes.
+    ///
+    /// ```
+    /// use circus::{prep, Instruction, State};
+    ///
+    /// let mut circuit = prep::ghz(8);
+    /// for target in 0..8 {
+    ///     circuit.push(Instruction::h(target));
+    ///     circuit.push(Instruction::measure(target));
+    /// }
+    ///
+    /// let run = |seed| {
+    ///     let mut state = State::seeded(8, seed);
+    ///     state.run(circuit.clone()).collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(run(3), run(3));
+    /// assert!((0..10).any(|seed| run(seed) != run(3)));
+    /// ```
+    pub fn seeded(n: usize, seed: u64) -> Self {
+        Self::packed_seeded(n, seed)
+    }
+
+    /// Create the computational basis state with qubit `j` set to `bits[j]`.
+    ///
+    /// Use [`State::packed_from_bits`] for a state packed into another word type.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::from_bits(&[true, false]);
+    /// assert_eq!(state.ket().unwrap(), " +|10>\n");
+    ///
+    /// let outcomes = state.measure_all();
+    /// assert!(outcomes.iter().all(|m| !m.is_random()));
+    /// assert!(outcomes[0].is_one() && outcomes[1].is_zero());
+    /// ```
+    pub fn from_bits(bits: &[bool]) -> Self {
+        Self::packed_from_bits(bits)
+    }
 
-        for i in 0..len {
+    /// Decode a tableau written by [`State::to_bytes`],
+    /// returning [`Error::InvalidBytes`] if `bytes` has the wrong length or an invalid field,
+    /// including a set bit beyond qubit `n` in any word.
+    ///
+    /// Use [`State::packed_from_bytes`] for a tableau written from another word type.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::packed_from_bytes(bytes)
+    }
+}
+
+impl<W: Word> State<W> {
+    /// Create a quantum state with `n` number of qubits, packed into words of type `W`.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::<u32>::packed(40);
+    /// state.h(0);
+    /// state.cx(0, 39);
+    /// assert!(state.measure(0).is_random());
+    /// assert_eq!(state.words, 2);
+    /// ```
+    pub fn packed(n: usize) -> Self {
+        let mut state = Self {
+            n,
+            xz: binary_matrix(n),
+            r: vec![0; 2 * n + 1].into_boxed_slice(),
+            words: n / W::WIDTH + 1,
+            rng: None,
+            #[cfg(feature = "op-stats")]
+            op_stats: OpStats::default(),
+        };
+        state.reset_all();
+        state
+    }
+
+    /// Create a quantum state like [`State::seeded`], packed into words of type `W`.
+    ///
+    /// ```
+    /// use circus::{prep, State};
+    ///
+    /// let mut narrow = State::<u32>::packed_seeded(40, 3);
+    /// narrow.run(prep::ghz(40)).for_each(drop);
+    ///
+    /// let mut wide = State::seeded(40, 3);
+    /// wide.run(prep::ghz(40)).for_each(drop);
+    /// assert_eq!(narrow.measure_all(), wide.measure_all());
+    /// ```
+    pub fn packed_seeded(n: usize, seed: u64) -> Self {
+        Self {
+            rng: Some(StdRng::seed_from_u64(seed)),
+            ..Self::packed(n)
+        }
+    }
+
+    /// Create a computational basis state like [`State::from_bits`], packed into words of type `W`.
+    pub fn packed_from_bits(bits: &[bool]) -> Self {
+        let mut state = Self::packed(bits.len());
+        for (target, _) in bits.iter().enumerate().filter(|(_, &bit)| bit) {
+            state.x(target);
+        }
+        state
+    }
+
+    /// Copy this state into words of type `V`, keeping its tableau and RNG.
+    ///
+    /// ```
+    /// use circus::{prep, State};
+    ///
+    /// let mut state = State::seeded(40, 3);
+    /// state.run(prep::ghz(40)).for_each(drop);
+    ///
+    /// let mut narrow = state.repack::<u32>();
+    /// assert_eq!(narrow.to_string(), state.to_string());
+    /// assert_eq!(narrow.measure_all(), state.measure_all());
+    /// assert!(narrow.repack::<u64>() == state);
+    /// ```
+    pub fn repack<V: Word>(&self) -> State<V> {
+        let mut state = State::packed(self.n);
+        for i in 0..2 * self.n + 1 {
+            repack_row(self.n, &self.xz[i], &mut state.xz[i]);
+        }
+        state.r.copy_from_slice(&self.r);
+        state.rng = self.rng.clone();
+        state
+    }
+
+    /// Reset every qubit to `|0>` in place, reusing the tableau's allocation.
+    ///
+    /// The RNG of a [`State::seeded`] state carries on from where it was, rather than restarting.
+    ///
+    /// ```
+    /// use circus::{prep, State};
+    ///
+    /// let mut state = State::new(40);
+    /// for _ in 0..3 {
+    ///     state.run(prep::ghz(40)).for_each(drop);
+    ///     state.measure_all();
+    ///     state.reset_all();
+    ///     assert!(state == State::new(40));
+    /// }
+    /// ```
+    pub fn reset_all(&mut self) {
+        let n = self.n;
+        for i in 0..2 * n + 1 {
+            self.xz[i].fill(W::ZERO);
             if i < n {
-                x[i][i >> 5] = PW[i & 31];
+                let (i5, pw) = W::locate(i);
+                *self.x_word_mut(i, i5) = pw;
             } else if i < 2 * n {
-                let j = i - n;
-                z[i][j >> 5] = PW[j & 31];
+                let (j5, pw) = W::locate(i - n);
+                *self.z_word_mut(i, j5) = pw;
             }
         }
+        self.r.fill(0);
+    }
+
+    /// Number of qubits in this state.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// assert_eq!(State::new(3).num_qubits(), 3);
+    /// ```
+    pub fn num_qubits(&self) -> usize {
+        self.n
+    }
+
+    /// Word `j` of the x bits of row `i`.
+    pub(crate) fn x_word(&self, i: usize, j: usize) -> W {
+        self.xz[i][2 * j]
+    }
+
+    /// Word `j` of the z bits of row `i`.
+    pub(crate) fn z_word(&self, i: usize, j: usize) -> W {
+        self.xz[i][2 * j + 1]
+    }
+
+    /// Mutable word `j` of the x bits of row `i`.
+    pub(crate) fn x_word_mut(&mut self, i: usize, j: usize) -> &mut W {
+        &mut self.xz[i][2 * j]
+    }
+
+    /// Mutable word `j` of the z bits of row `i`.
+    pub(crate) fn z_word_mut(&mut self, i: usize, j: usize) -> &mut W {
+        &mut self.xz[i][2 * j + 1]
+    }
+
+    /// The x bit of qubit `j` in row `i`.
+    pub(crate) fn x_bit(&self, i: usize, j: usize) -> bool {
+        let (j5, pw) = W::locate(j);
+        self.x_word(i, j5) & pw != W::ZERO
+    }
 
-        Self { n, x, z, r, over32 }
+    /// The z bit of qubit `j` in row `i`.
+    pub(crate) fn z_bit(&self, i: usize, j: usize) -> bool {
+        let (j5, pw) = W::locate(j);
+        self.z_word(i, j5) & pw != W::ZERO
     }
 
-    pub fn run<I>(&mut self, iter: I) -> Measurements<'_, I::IntoIter>
+    /// Number of row operations performed on this state's tableau so far.
+    ///
+    /// Gates act on the columns of the tableau, so only measurement and
+    /// Gaussian elimination perform row operations.
+    ///
+    /// ```
+    /// use circus::{state::OpStats, State};
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    /// assert_eq!(state.op_stats(), OpStats::default());
+    ///
+    /// state.measure(1);
+    /// let stats = state.op_stats();
+    /// assert_eq!(stats.rowcopy, 1);
+    /// assert_eq!(stats.rowmult, stats.clifford);
+    /// ```
+    #[cfg(feature = "op-stats")]
+    pub fn op_stats(&self) -> OpStats {
+        self.op_stats
+    }
+
+    /// Run a circuit of instructions, returning an iterator over its measurements.
+    /// Each instruction is applied lazily as the iterator advances.
+    ///
+    /// # Panics
+    ///
+    /// The iterator panics when it reaches an instruction acting on a qubit this state doesn't have,
+    /// or an [`Instruction::Detector`] referring to a measurement that hasn't been made yet.
+    /// Use [`State::try_run`] to check the circuit first.
+    ///
+    /// ```
+    /// use circus::{
+    ///     gate::{CNotGate, Gates, HadamardGate},
+    ///     Instruction, State,
+    /// };
+    ///
+    /// let mut state = State::new(4);
+    /// let circuit = [
+    ///     Instruction::GateBroadcast(vec![
+    ///         Gates::Hadamard(HadamardGate { target: 0 }),
+    ///         Gates::Hadamard(HadamardGate { target: 2 }),
+    ///     ]),
+    ///     Instruction::GateBroadcast(vec![
+    ///         Gates::CNot(CNotGate { control: 0, target: 1 }),
+    ///         Gates::CNot(CNotGate { control: 2, target: 3 }),
+    ///     ]),
+    ///     Instruction::Measure { target: 0 },
+    ///     Instruction::Measure { target: 1 },
+    /// ];
+    /// let m: Vec<_> = state.run(circuit).collect();
+    ///
+    /// assert!(m[0].is_random());
+    /// assert!(!m[1].is_random());
+    /// assert_eq!(m[0].is_one(), m[1].is_one());
+    /// ```
+    pub fn run<I>(&mut self, iter: I) -> Measurements<'_, I::IntoIter, W>
     where
         I: IntoIterator<Item = Instruction>,
     {
         Measurements {
             state: self,
             iter: iter.into_iter(),
+            record: Vec::new(),
+            detectors: Vec::new(),
+            #[cfg(feature = "tracing")]
+            position: 0,
         }
     }
 
+    /// Run a circuit of instructions and collect its measurements,
+    /// first checking it with [`validate`](crate::validate).
+    ///
+    /// This returns [`Error::Invalid`] without changing this state if the circuit can't be run.
+    ///
+    /// ```
+    /// use circus::{Error, Instruction, State, ValidationError};
+    ///
+    /// let circuit = [
+    ///     Instruction::measure(0),
+    ///     Instruction::Detector {
+    ///         measurement_indices: vec![0, 1],
+    ///     },
+    ///     Instruction::measure(1),
+    /// ];
+    ///
+    /// let mut state = State::new(2);
+    /// assert_eq!(
+    ///     state.try_run(&circuit),
+    ///     Err(Error::Invalid(ValidationError::MeasurementOutOfRange {
+    ///         index: 1,
+    ///         measurement: 1
+    ///     }))
+    /// );
+    /// assert!(state == State::new(2));
+    ///
+    /// let m = state.try_run(&circuit[..1]).unwrap();
+    /// assert!(m[0].is_zero());
+    /// ```
+    pub fn try_run(&mut self, instructions: &[Instruction]) -> Result<Vec<Measurement>, Error> {
+        crate::validate(self.n, instructions)?;
+        Ok(self.run(instructions.iter().cloned()).collect())
+    }
+
+    /// Run a circuit and return its measurements, calling `callback` with the number of instructions run
+    /// each time another `every` of them have finished.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is 0.
+    ///
+    /// ```
+    /// use circus::{Instruction, State};
+    ///
+    /// let circuit: Vec<_> = (0..10).map(|_| Instruction::h(0)).collect();
+    ///
+    /// let mut progress = Vec::new();
+    /// let mut state = State::new(1);
+    /// state.run_with_progress(circuit, 3, |count| progress.push(count));
+    /// assert_eq!(progress, [3, 6, 9]);
+    /// ```
+    pub fn run_with_progress<I, F>(
+        &mut self,
+        instructions: I,
+        every: usize,
+        mut callback: F,
+    ) -> Vec<Measurement>
+    where
+        I: IntoIterator<Item = Instruction>,
+        F: FnMut(usize),
+    {
+        assert!(every > 0, "progress interval must be positive");
+
+        // An instruction has finished once the next one is requested, or once the circuit ends
+        let mut count = 0;
+        let measurements = self
+            .run(instructions.into_iter().inspect(|_| {
+                if count > 0 && count % every == 0 {
+                    callback(count);
+                }
+                count += 1;
+            }))
+            .collect();
+        if count > 0 && count % every == 0 {
+            callback(count);
+        }
+        measurements
+    }
+
+    /// Run a circuit until the first measurement for which `stop` returns `true`,
+    /// returning the measurements made up to and including it.
+    ///
+    /// ```
+    /// use circus::{Instruction, State};
+    ///
+    /// let circuit = [
+    ///     Instruction::measure(0),
+    ///     Instruction::x(1),
+    ///     Instruction::measure(1),
+    ///     Instruction::measure(0),
+    /// ];
+    /// let mut state = State::new(2);
+    /// let measurements = state.run_until(circuit, |m| m.is_one());
+    /// assert_eq!(measurements, [false, true]);
+    ///
+    /// // Keep measuring |+> until it gives a 1
+    /// let circuit = (0..64).flat_map(|_| [Instruction::h(0), Instruction::measure(0)]);
+    /// let mut state = State::seeded(1, 5);
+    /// let measurements = state.run_until(circuit, |m| m.is_one());
+    /// let (last, rest) = measurements.split_last().unwrap();
+    /// assert!(last.is_one() && rest.iter().all(|m| m.is_zero()));
+    /// ```
+    pub fn run_until<I, F>(&mut self, instructions: I, mut stop: F) -> Vec<Measurement>
+    where
+        I: IntoIterator<Item = Instruction>,
+        F: FnMut(&Measurement) -> bool,
+    {
+        let mut measurements = Vec::new();
+        for measurement in self.run(instructions) {
+            measurements.push(measurement);
+            if stop(&measurement) {
+                break;
+            }
+        }
+        measurements
+    }
+
+    /// Run a circuit written for `qubit_map.len()` qubits on this state,
+    /// where its qubit `q` is this state's qubit `qubit_map[q]`, and return its measurements.
+    ///
+    /// The circuit is checked with [`validate`](crate::validate) before anything runs,
+    /// and `qubit_map` must list distinct qubits of this state.
+    ///
+    /// ```
+    /// use circus::{prep, Error, State};
+    ///
+    /// let mut state = State::new(5);
+    /// state.run_on_qubits(&prep::ghz(2), &[2, 4]).unwrap();
+    ///
+    /// let stabilizers: Vec<_> = state.iter_stabilizers().map(|p| p.to_string()).collect();
+    /// assert_eq!(stabilizers, ["+ZIIII", "+IZIII", "+IIXIX", "+IIIZI", "+IIZIZ"]);
+    /// assert!(state.are_entangled(2, 4));
+    ///
+    /// assert_eq!(
+    ///     state.run_on_qubits(&prep::ghz(2), &[2, 5]).err(),
+    ///     Some(Error::QubitOutOfRange { qubit: 5, n: 5 })
+    /// );
+    /// assert_eq!(
+    ///     state.run_on_qubits(&prep::ghz(2), &[3, 3]).err(),
+    ///     Some(Error::RepeatedQubit { qubit: 3 })
+    /// );
+    /// ```
+    pub fn run_on_qubits(
+        &mut self,
+        instructions: &[Instruction],
+        qubit_map: &[usize],
+    ) -> Result<Vec<Measurement>, Error> {
+        crate::validate(qubit_map.len(), instructions)?;
+        self.check_distinct(qubit_map)?;
+
+        let instructions = instructions
+            .iter()
+            .map(|instruction| instruction.map_qubits(|q| qubit_map[q]));
+        Ok(self.run(instructions).collect())
+    }
+
+    /// Run `instructions` for `shots` shots, each on a copy of this state,
+    /// and return the number of distinct measurement records observed.
+    ///
+    /// This is a quick estimate of the size of the state's support without building a histogram.
+    /// The state itself is left unchanged apart from its RNG, which advances so later calls draw new samples.
+    ///
+    /// ```
+    /// use circus::{prep, Instruction, State};
+    ///
+    /// let mut circuit = prep::ghz(4);
+    /// circuit.extend((0..4).map(Instruction::measure));
+    ///
+    /// let mut state = State::seeded(4, 3);
+    /// assert_eq!(state.sample_unique(&circuit, 1), 1);
+    /// assert_eq!(state.sample_unique(&circuit, 100), 2);
+    /// assert_eq!(state.sample_unique(&circuit, 1000), 2);
+    ///
+    /// circuit.extend((0..4).flat_map(|q| [Instruction::h(q), Instruction::measure(q)]));
+    /// assert!(state.sample_unique(&circuit, 1000) > 2);
+    /// ```
+    pub fn sample_unique(&mut self, instructions: &[Instruction], shots: usize) -> usize {
+        let mut seen = HashSet::new();
+        for _ in 0..shots {
+            let mut shot = self.clone();
+            let bits: Vec<_> = shot
+                .run(instructions.iter().cloned())
+                .map(|m| m.is_one())
+                .collect();
+            seen.insert(bits);
+            self.rng = shot.rng;
+        }
+        seen.len()
+    }
+
+    /// Parse and run a compact circuit such as `"h0 s1 cx0,2 m3"`, returning its measurements.
+    ///
+    /// Each word is a [Stim](crate::stim) instruction name, in any case, followed by its targets separated by commas.
+    /// Nothing is run if any word fails to parse, and errors report the word's position starting at 1 as their line.
+    ///
+    /// ```
+    /// use circus::{stim::StimError, State};
+    ///
+    /// let mut state = State::new(2);
+    /// assert!(state.apply_spec("h0 cx0,1").unwrap().is_empty());
+    ///
+    /// let stabilizers: Vec<_> = state.iter_stabilizers().map(|p| p.to_string()).collect();
+    /// assert_eq!(stabilizers, ["+XX", "+ZZ"]);
+    ///
+    /// let m = state.apply_spec("m0,1").unwrap();
+    /// assert!(m[0].is_random() && m[0].is_one() == m[1].is_one());
+    ///
+    /// assert!(matches!(
+    ///     state.apply_spec("h0 t1"),
+    ///     Err(StimError::UnknownInstruction { line: 2, .. })
+    /// ));
+    /// ```
+    pub fn apply_spec(&mut self, spec: &str) -> Result<Vec<Measurement>, StimError> {
+        let instructions = stim::parse_spec(spec)?;
+        Ok(self.run(instructions).collect())
+    }
+
+    /// Phase exponent `r` of stabilizer generator `index`, which is 0 for `+` and 2 for `-`.
+    ///
+    /// ```
+    /// use circus::{Error, State};
+    ///
+    /// let mut state = State::new(3);
+    /// state.x(1);
+    ///
+    /// let signs: Vec<_> = (0..3).map(|i| state.stabilizer_sign(i).unwrap()).collect();
+    /// assert_eq!(signs, [0, 2, 0]);
+    /// assert_eq!(
+    ///     state.stabilizer_sign(3),
+    ///     Err(Error::GeneratorOutOfRange { index: 3, n: 3 })
+    /// );
+    /// ```
+    pub fn stabilizer_sign(&self, index: usize) -> Result<i32, Error> {
+        if index >= self.n {
+            return Err(Error::GeneratorOutOfRange { index, n: self.n });
+        }
+        Ok(self.r[self.n + index])
+    }
+
     /// Apply the controlled-NOT gate, also known as the controlled-x (CX) gate.
     /// It performs a NOT on the `target` whenever the `control` is in state `|1⟩`.
-    pub fn cx(&mut self, target: usize, control: usize) {
-        let gate = CNotGate { target, control };
+    ///
+    /// A CNOT whose `control` is its `target` leaves the state unchanged,
+    /// while [`State::try_apply_gate`] returns [`Error::RepeatedQubit`] for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `control` or `target` isn't a qubit of this state.
+    /// Use [`State::try_cx`] to get an error instead.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// let before = state.clone();
+    ///
+    /// state.cx(0, 0);
+    /// state.cz(0, 0);
+    /// state.iswap(0, 0);
+    /// assert!(state == before);
+    /// ```
+    pub fn cx(&mut self, control: usize, target: usize) {
+        self.assert_qubit(control);
+        self.assert_qubit(target);
+        let gate = CNotGate { control, target };
+        gate.apply(self);
+    }
+
+    /// Apply a stored gate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the gate acts on a qubit that isn't in this state.
+    /// Use [`State::try_apply_gate`] to get an error instead.
+    ///
+    /// ```
+    /// use circus::{
+    ///     gate::{CNotGate, Gates, HadamardGate},
+    ///     State,
+    /// };
+    ///
+    /// let gates = [
+    ///     Gates::Hadamard(HadamardGate { target: 0 }),
+    ///     Gates::CNot(CNotGate { control: 0, target: 1 }),
+    /// ];
+    ///
+    /// let mut a = State::new(2);
+    /// for gate in &gates {
+    ///     a.apply_gate(gate);
+    /// }
+    ///
+    /// let mut b = State::new(2);
+    /// b.h(0);
+    /// b.cx(0, 1);
+    ///
+    /// assert_eq!(a.to_string(), b.to_string());
+    /// ```
+    pub fn apply_gate(&mut self, gate: &Gates) {
+        gate.qubits().for_each(|qubit| self.assert_qubit(qubit));
+        gate.apply(self);
+    }
+
+    /// Apply a stored gate, first checking that its qubits are distinct qubits of this state.
+    ///
+    /// ```
+    /// use circus::{
+    ///     gate::{CNotGate, Gates, HadamardGate},
+    ///     Error, State,
+    /// };
+    ///
+    /// let mut state = State::new(2);
+    /// assert_eq!(
+    ///     state.try_apply_gate(&Gates::Hadamard(HadamardGate { target: 2 })),
+    ///     Err(Error::QubitOutOfRange { qubit: 2, n: 2 })
+    /// );
+    /// assert_eq!(
+    ///     state.try_apply_gate(&Gates::CNot(CNotGate { control: 1, target: 1 })),
+    ///     Err(Error::RepeatedQubit { qubit: 1 })
+    /// );
+    /// assert!(state == State::new(2));
+    /// ```
+    pub fn try_apply_gate(&mut self, gate: &Gates) -> Result<(), Error> {
+        let qubits: Vec<_> = gate.qubits().collect();
+        self.check_distinct(&qubits)?;
+        gate.apply(self);
+        Ok(())
+    }
+
+    /// Apply the Hadamard gate like [`State::h`], or return [`Error::QubitOutOfRange`]
+    /// without changing this state if `target` isn't one of its qubits.
+    ///
+    /// ```
+    /// use circus::{Error, State};
+    ///
+    /// let mut state = State::new(2);
+    /// assert_eq!(state.try_h(1), Ok(()));
+    /// assert_eq!(state.try_h(2), Err(Error::QubitOutOfRange { qubit: 2, n: 2 }));
+    ///
+    /// let mut expected = State::new(2);
+    /// expected.h(1);
+    /// assert_eq!(state, expected);
+    /// ```
+    pub fn try_h(&mut self, target: usize) -> Result<(), Error> {
+        self.try_apply_gate(&Gates::Hadamard(HadamardGate { target }))
+    }
+
+    /// Apply the S gate like [`State::s`], or return [`Error::QubitOutOfRange`]
+    /// without changing this state if `target` isn't one of its qubits.
+    pub fn try_s(&mut self, target: usize) -> Result<(), Error> {
+        self.try_apply_gate(&Gates::Phase(PhaseGate { target }))
+    }
+
+    /// Apply the inverse S gate like [`State::s_dag`], or return [`Error::QubitOutOfRange`]
+    /// without changing this state if `target` isn't one of its qubits.
+    pub fn try_s_dag(&mut self, target: usize) -> Result<(), Error> {
+        self.try_apply_gate(&Gates::PhaseDagger(PhaseDaggerGate { target }))
+    }
+
+    /// Apply the Pauli X gate like [`State::x`], or return [`Error::QubitOutOfRange`]
+    /// without changing this state if `target` isn't one of its qubits.
+    pub fn try_x(&mut self, target: usize) -> Result<(), Error> {
+        self.try_apply_gate(&Gates::PauliX(PauliXGate { target }))
+    }
+
+    /// Apply the Pauli Y gate like [`State::y`], or return [`Error::QubitOutOfRange`]
+    /// without changing this state if `target` isn't one of its qubits.
+    pub fn try_y(&mut self, target: usize) -> Result<(), Error> {
+        self.try_apply_gate(&Gates::PauliY(PauliYGate { target }))
+    }
+
+    /// Apply the Pauli Z gate like [`State::z`], or return [`Error::QubitOutOfRange`]
+    /// without changing this state if `target` isn't one of its qubits.
+    pub fn try_z(&mut self, target: usize) -> Result<(), Error> {
+        self.try_apply_gate(&Gates::PauliZ(PauliZGate { target }))
+    }
+
+    /// Apply a CNOT like [`State::cx`], checking its qubits like [`State::try_apply_gate`].
+    ///
+    /// Unlike `cx`, which treats a CNOT of a qubit onto itself as a no-op,
+    /// this returns [`Error::RepeatedQubit`] if `control` and `target` are the same.
+    ///
+    /// ```
+    /// use circus::{Error, State};
+    ///
+    /// let mut state = State::new(2);
+    /// assert_eq!(state.try_cx(0, 2), Err(Error::QubitOutOfRange { qubit: 2, n: 2 }));
+    /// assert_eq!(state.try_cx(1, 1), Err(Error::RepeatedQubit { qubit: 1 }));
+    /// assert_eq!(state, State::new(2));
+    /// ```
+    pub fn try_cx(&mut self, control: usize, target: usize) -> Result<(), Error> {
+        self.try_apply_gate(&Gates::CNot(CNotGate { control, target }))
+    }
+
+    /// Apply a CZ gate like [`State::cz`], checking its qubits like [`State::try_cx`].
+    pub fn try_cz(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        self.try_apply_gate(&Gates::CZ(CZGate { a, b }))
+    }
+
+    /// Apply the iSWAP gate like [`State::iswap`], checking its qubits like [`State::try_cx`].
+    pub fn try_iswap(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        self.try_apply_gate(&Gates::ISwap(ISwapGate { a, b }))
+    }
+
+    /// Apply a layer of gates acting on disjoint qubits, such as a single clock cycle of a device.
+    ///
+    /// Debug builds panic if two of the gates share a qubit, and all builds panic if a gate acts on a qubit
+    /// that isn't in this state, before applying any of the gates.
+    ///
+    /// ```
+    /// use circus::{
+    ///     gate::{Gates, HadamardGate},
+    ///     State,
+    /// };
+    ///
+    /// let layer: Vec<_> = (0..3)
+    ///     .map(|target| Gates::Hadamard(HadamardGate { target }))
+    ///     .collect();
+    ///
+    /// let mut a = State::new(3);
+    /// a.apply_layer(&layer);
+    ///
+    /// let mut b = State::new(3);
+    /// b.h(0);
+    /// b.h(1);
+    /// b.h(2);
+    ///
+    /// assert!(a == b);
+    /// ```
+    pub fn apply_layer(&mut self, gates: &[Gates]) {
+        gates
+            .iter()
+            .flat_map(Gates::qubits)
+            .for_each(|qubit| self.assert_qubit(qubit));
+
+        #[cfg(debug_assertions)]
+        {
+            let mut used = vec![false; self.n];
+            for qubit in gates.iter().flat_map(Gates::qubits) {
+                assert!(!used[qubit], "qubit {qubit} appears twice in one layer");
+                used[qubit] = true;
+            }
+        }
+
+        for gate in gates {
+            gate.apply(self);
+        }
+    }
+
+    /// Apply a CNOT from the `control` to each of the `targets`, as in GHZ state preparation.
+    ///
+    /// All of the CNOTs are applied in a single pass over the tableau rows,
+    /// updating a whole word of targets at a time rather than making one pass for each target.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `control` or any of the `targets` isn't a qubit of this state.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut a = State::new(4);
+    /// a.h(0);
+    /// a.cx_fanout(0, &[1, 2, 3]);
+    ///
+    /// let mut b = State::new(4);
+    /// b.h(0);
+    /// b.cx(0, 1);
+    /// b.cx(0, 2);
+    /// b.cx(0, 3);
+    ///
+    /// assert_eq!(a.to_string(), b.to_string());
+    ///
+    /// // Signs and Y terms are tracked across words, a repeated target cancels,
+    /// // and the control as a target is skipped like `cx(3, 3)`
+    /// let mut state = State::new(80);
+    /// for q in 0..80 {
+    ///     state.h(q);
+    ///     if q % 3 == 0 {
+    ///         state.p(q);
+    ///     }
+    ///     state.cx(q, (q * 7 + 1) % 80);
+    /// }
+    /// let targets = [1, 65, 3, 5, 79, 65, 20];
+    ///
+    /// let mut a = state.clone();
+    /// a.cx_fanout(3, &targets);
+    ///
+    /// let mut b = state;
+    /// targets.iter().for_each(|&t| b.cx(3, t));
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn cx_fanout(&mut self, control: usize, targets: &[usize]) {
+        self.assert_qubit(control);
+        targets.iter().for_each(|&target| self.assert_qubit(target));
+
+        // CNOTs sharing a control commute, so the targets can be applied in bit order,
+        // and a repeated target cancels
+        let mut masks = vec![W::ZERO; self.words];
+        for &target in targets.iter().filter(|&&target| target != control) {
+            let (t5, pw) = W::locate(target);
+            masks[t5] ^= pw;
+        }
+
+        let (c5, pwc) = W::locate(control);
+        for i in 0..2 * self.n {
+            let row = &mut self.xz[i];
+            let x_control = row[2 * c5] & pwc != W::ZERO;
+            let mut z_control = row[2 * c5 + 1] & pwc != W::ZERO;
+            let mut sign = false;
+
+            for (xz, &mask) in row.chunks_exact_mut(2).zip(&masks) {
+                let mut zs = xz[1] & mask;
+                if x_control {
+                    // Each CNOT flips the sign when its target has a Z and an X equal to the control's Z
+                    while zs != W::ZERO {
+                        let bit = zs.lowest_one();
+                        sign ^= (xz[0] & bit != W::ZERO) == z_control;
+                        z_control = !z_control;
+                        zs ^= bit;
+                    }
+                    xz[0] ^= mask;
+                } else {
+                    z_control ^= zs.count_ones() & 1 == 1;
+                }
+            }
+
+            if z_control != (row[2 * c5 + 1] & pwc != W::ZERO) {
+                row[2 * c5 + 1] ^= pwc;
+            }
+            if sign {
+                self.r[i] = (self.r[i] + 2) % 4;
+            }
+        }
+    }
+
+    /// Apply a CNOT to each `(control, target)` pair in order.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut a = State::new(4);
+    /// a.h_all(&[0, 2]);
+    /// a.cx_pairs(&[(0, 1), (2, 3)]);
+    ///
+    /// let mut b = State::new(4);
+    /// b.h(0);
+    /// b.h(2);
+    /// b.cx(0, 1);
+    /// b.cx(2, 3);
+    ///
+    /// assert_eq!(a.to_string(), b.to_string());
+    /// ```
+    pub fn cx_pairs(&mut self, pairs: &[(usize, usize)]) {
+        for &(control, target) in pairs {
+            self.cx(control, target);
+        }
+    }
+
+    /// Apply a layer of CZ gates, one for each pair `(a, b)`, in a single pass over the tableau.
+    ///
+    /// CZ gates commute, so their order doesn't matter.
+    /// This is the entangling layer of a graph state, whose stabilizers are `X_v` times `Z_u`
+    /// for each neighbour `u` of each vertex `v`.
+    ///
+    /// A pair of the same qubit twice is skipped, as in [`State::cz`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if a qubit of any pair isn't a qubit of this state, before applying any of the gates.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// // A triangle with a tail
+    /// let edges = [(0, 1), (1, 2), (2, 0), (2, 3)];
+    ///
+    /// let mut graph = State::new(4);
+    /// graph.h_all(&[0, 1, 2, 3]);
+    /// graph.cz_layer(&edges);
+    ///
+    /// let stabilizers: Vec<_> = graph.iter_stabilizers().map(|p| p.to_string()).collect();
+    /// assert_eq!(stabilizers, ["+XZZI", "+ZXZI", "+ZZXZ", "+IIZX"]);
+    ///
+    /// let mut each = State::new(4);
+    /// each.h_all(&[0, 1, 2, 3]);
+    /// for (a, b) in edges {
+    ///     each.cz(a, b);
+    /// }
+    /// assert_eq!(graph, each);
+    ///
+    /// // Signs and Y components are updated too
+    /// for state in [&mut graph, &mut each] {
+    ///     state.x(1);
+    ///     state.s(2);
+    /// }
+    /// graph.cz_layer(&edges);
+    /// for (a, b) in edges {
+    ///     each.cz(a, b);
+    /// }
+    /// assert_eq!(graph, each);
+    /// ```
+    pub fn cz_layer(&mut self, pairs: &[(usize, usize)]) {
+        for &(a, b) in pairs {
+            self.assert_qubit(a);
+            self.assert_qubit(b);
+        }
+
+        // `cz_row` on a single qubit flips its z bit twice, so it's already a no-op
+        for i in 0..2 * self.n {
+            for &(a, b) in pairs {
+                cz_row(self, i, a, b);
+            }
+        }
+    }
+
+    /// Apply the controlled-Z (CZ) gate, flipping the phase of `|11⟩` on qubits `a` and `b`.
+    ///
+    /// This leaves the state unchanged if `a` and `b` are the same qubit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` isn't a qubit of this state. Use [`State::try_cz`] to get an error instead.
+    pub fn cz(&mut self, a: usize, b: usize) {
+        self.assert_qubit(a);
+        self.assert_qubit(b);
+        let gate = CZGate { a, b };
         gate.apply(self);
     }
 
     /// Apply the Hadamard gate.
     /// Rotates the states `|0⟩` and `|1⟩` to `|+⟩` and `|-⟩`, respectively.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state. Use [`State::try_h`] to get an error instead.
     pub fn h(&mut self, target: usize) {
+        self.assert_qubit(target);
         let gate = HadamardGate { target };
         gate.apply(self);
     }
 
+    /// Apply the Hadamard gate to each of the `targets` in order.
+    pub fn h_all(&mut self, targets: &[usize]) {
+        for &target in targets {
+            self.h(target);
+        }
+    }
+
+    /// Apply the Hadamard gate to every qubit, such as before measuring the whole register in the X basis.
+    ///
+    /// This is [`State::h_range`] over all qubits, taking a single pass over the tableau
+    /// rather than one per qubit.
+    ///
+    /// ```
+    /// use circus::{prep, State};
+    ///
+    /// let mut state = State::new(40);
+    /// state.run(prep::ghz(40)).for_each(drop);
+    /// state.p(3);
+    ///
+    /// let mut a = state.clone();
+    /// a.h_all_qubits();
+    ///
+    /// let mut b = state.clone();
+    /// b.h_all(&(0..40).collect::<Vec<_>>());
+    /// assert!(a == b);
+    ///
+    /// a.h_all_qubits();
+    /// assert!(a == state);
+    /// ```
+    pub fn h_all_qubits(&mut self) {
+        self.h_range(0..self.n);
+    }
+
+    /// Apply the Hadamard gate to each qubit in `range` in a single pass over the tableau.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` extends past the last qubit.
+    ///
+    /// ```
+    /// use circus::{prep, State};
+    ///
+    /// let mut state = State::new(80);
+    /// state.run(prep::ghz(80)).for_each(drop);
+    /// state.p(62);
+    ///
+    /// let mut a = state.clone();
+    /// a.h_range(0..3);
+    ///
+    /// let mut b = state.clone();
+    /// b.h(0);
+    /// b.h(1);
+    /// b.h(2);
+    /// assert_eq!(a, b);
+    ///
+    /// a.h_range(61..67);
+    /// b.h_all(&[61, 62, 63, 64, 65, 66]);
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn h_range(&mut self, range: Range<usize>) {
+        let masks = self.range_masks(range);
+        for i in 0..2 * self.n {
+            let mut ys = 0;
+            for (xz, &mask) in self.xz[i].chunks_exact_mut(2).zip(&masks) {
+                ys ^= (xz[0] & xz[1] & mask).count_ones();
+                let swapped = (xz[0] ^ xz[1]) & mask;
+                xz[0] ^= swapped;
+                xz[1] ^= swapped;
+            }
+            if ys & 1 == 1 {
+                self.r[i] = (self.r[i] + 2) % 4;
+            }
+        }
+    }
+
+    /// Apply the iSWAP gate, swapping qubits `a` and `b`
+    /// while mapping `|01⟩` to `i|10⟩` and `|10⟩` to `i|01⟩`.
+    /// This leaves the state unchanged if `a` and `b` are the same qubit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` isn't a qubit of this state. Use [`State::try_iswap`] to get an error instead.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// // Prepare |01⟩ (X = HSSH)
+    /// let mut state = State::new(2);
+    /// state.h(1);
+    /// state.p(1);
+    /// state.p(1);
+    /// state.h(1);
+    ///
+    /// state.iswap(0, 1);
+    ///
+    /// let a = state.measure(0);
+    /// let b = state.measure(1);
+    /// assert!(a.is_one() && !a.is_random());
+    /// assert!(b.is_zero() && !b.is_random());
+    /// ```
+    ///
+    /// A global phase of `i` isn't observable, so the phase shows up against `|00⟩` in a superposition,
+    /// where [`State::ket`] writes qubit 0 first:
+    /// ```
+    /// use circus::State;
+    ///
+    /// for (q, before, after) in [(0, "|10>", "+i|01>"), (1, "|01>", "+i|10>")] {
+    ///     let mut state = State::new(2);
+    ///     state.h(q);
+    ///     assert_eq!(state.ket().unwrap(), format!(" +|00>\n +{before}\n"));
+    ///
+    ///     state.iswap(0, 1);
+    ///     assert_eq!(state.ket().unwrap(), format!(" +|00>\n{after}\n"));
+    /// }
+    /// ```
+    ///
+    /// Applying the inverse decomposition restores the original tableau:
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// let before = state.to_string();
+    ///
+    /// state.iswap(0, 1);
+    ///
+    /// state.h(1);
+    /// state.cx(1, 0);
+    /// state.cx(0, 1);
+    /// state.h(0);
+    /// for _ in 0..3 {
+    ///     state.p(0);
+    ///     state.p(1);
+    /// }
+    ///
+    /// assert_eq!(state.to_string(), before);
+    /// ```
+    pub fn iswap(&mut self, a: usize, b: usize) {
+        self.assert_qubit(a);
+        self.assert_qubit(b);
+        let gate = ISwapGate { a, b };
+        gate.apply(self);
+    }
+
     /// Apply a phase gate (|0⟩->|0⟩, |1⟩->i|1⟩) to the `target` qubit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state. Use [`State::try_s`] to get an error instead.
     pub fn p(&mut self, target: usize) {
+        self.assert_qubit(target);
         let gate = PhaseGate { target };
         gate.apply(self);
     }
 
-    /// Measure the `target` qubit.
-    pub fn measure(&mut self, target: usize) -> Measurement {
-        let mut is_indeterminate = false;
+    /// Apply the S gate to the `target` qubit, which is the same as [`State::p`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state. Use [`State::try_s`] to get an error instead.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut plus = State::new(1);
+    /// plus.h(0);
+    ///
+    /// let mut state = plus.clone();
+    /// state.s(0);
+    /// assert!(state != plus);
+    /// for _ in 0..3 {
+    ///     state.s(0);
+    /// }
+    /// assert!(state == plus);
+    /// ```
+    pub fn s(&mut self, target: usize) {
+        self.p(target);
+    }
 
-        let b5 = target >> 5;
-        let pw = PW[target & 31];
-        let mut p = 0;
+    /// Apply the inverse phase gate (|0⟩->|0⟩, |1⟩->-i|1⟩) to the `target` qubit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state. Use [`State::try_s_dag`] to get an error instead.
+    pub fn s_dag(&mut self, target: usize) {
+        self.assert_qubit(target);
+        let gate = PhaseDaggerGate { target };
+        gate.apply(self);
+    }
 
-        // loop over stabilizer generators
-        for a in 0..self.n {
-            // if a Zbar does NOT commute with Z_b (the operator being measured), then outcome is random
-            if (self.x[a + self.n][b5] & pw) > 0 {
-                is_indeterminate = true;
-                break;
+    /// Apply the S gate `k` times to the `target` qubit, as a single gate since `S^4` is the identity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state, even if `k` is a multiple of 4.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut plus = State::new(1);
+    /// plus.h(0);
+    ///
+    /// let mut state = plus.clone();
+    /// state.s_pow(0, 4);
+    /// assert_eq!(state, plus);
+    ///
+    /// let mut z = plus.clone();
+    /// z.z(0);
+    /// state.s_pow(0, 2);
+    /// assert_eq!(state, z);
+    ///
+    /// let mut s_dag = plus.clone();
+    /// s_dag.s_dag(0);
+    /// plus.s_pow(0, 7);
+    /// assert_eq!(plus, s_dag);
+    /// ```
+    pub fn s_pow(&mut self, target: usize, k: u32) {
+        self.assert_qubit(target);
+        match k % 4 {
+            0 => {}
+            1 => self.s(target),
+            2 => self.z(target),
+            _ => self.s_dag(target),
+        }
+    }
+
+    /// Apply the Pauli X (NOT) gate to the `target` qubit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state. Use [`State::try_x`] to get an error instead.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(1);
+    /// state.x(0);
+    /// assert!(state.measure(0).is_one());
+    /// ```
+    pub fn x(&mut self, target: usize) {
+        self.assert_qubit(target);
+        let gate = PauliXGate { target };
+        gate.apply(self);
+    }
+
+    /// Apply the Pauli X gate to each qubit in `range` in a single pass over the tableau.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` extends past the last qubit.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(80);
+    /// state.x_range(62..66);
+    /// let bits: Vec<_> = (0..80).map(|q| (62..66).contains(&q)).collect();
+    /// assert_eq!(state, State::from_bits(&bits));
+    ///
+    /// state.h(63);
+    /// let mut a = state.clone();
+    /// a.x_range(0..80);
+    /// for q in 0..80 {
+    ///     state.x(q);
+    /// }
+    /// assert_eq!(a, state);
+    /// ```
+    pub fn x_range(&mut self, range: Range<usize>) {
+        let masks = self.range_masks(range);
+        for i in 0..2 * self.n {
+            // X flips the sign of each Z and Y in the row
+            let zs = self.xz[i]
+                .chunks_exact(2)
+                .zip(&masks)
+                .fold(0, |zs, (xz, &mask)| zs ^ (xz[1] & mask).count_ones());
+            if zs & 1 == 1 {
+                self.r[i] = (self.r[i] + 2) % 4;
             }
-            p += 1;
         }
+    }
 
-        if is_indeterminate {
-            // Outcome is indeterminate
-            self.rowcopy(p, p + self.n); // Set Xbar_p := Zbar_p
-            self.rowset(p + self.n, target + self.n); // Set Zbar_p := Z_b
-            self.r[p + self.n] = 2 * (rand::random::<i32>() % 2); // moment of quantum randomness
-            for i in 0..2 * self.n {
-                // Now update the Xbar's and Zbar's that don't commute with
-                if (i != p) && (self.x[i][b5] & pw > 0) {
-                    self.rowmult(i, p);
-                } // Z_b
+    /// Apply the Pauli Y gate to the `target` qubit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state. Use [`State::try_y`] to get an error instead.
+    pub fn y(&mut self, target: usize) {
+        self.assert_qubit(target);
+        let gate = PauliYGate { target };
+        gate.apply(self);
+    }
+
+    /// Apply the Pauli Z gate to the `target` qubit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state. Use [`State::try_z`] to get an error instead.
+    pub fn z(&mut self, target: usize) {
+        self.assert_qubit(target);
+        let gate = PauliZGate { target };
+        gate.apply(self);
+    }
+
+    /// Apply the single-qubit Pauli `pauli` to the `target` qubit, such as an error drawn from a noise model.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state, even if `pauli` is the identity.
+    ///
+    /// ```
+    /// use circus::{pauli::Pauli, State};
+    ///
+    /// let mut state = State::new(3);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    /// let start = state.clone();
+    ///
+    /// state.apply_error(1, Pauli::X);
+    /// assert!(state != start);
+    /// state.apply_error(1, Pauli::X);
+    /// assert!(state == start);
+    ///
+    /// state.apply_error(2, Pauli::I);
+    /// assert!(state == start);
+    /// ```
+    pub fn apply_error(&mut self, target: usize, pauli: Pauli) {
+        self.assert_qubit(target);
+        match pauli {
+            Pauli::I => {}
+            Pauli::X => self.x(target),
+            Pauli::Y => self.y(target),
+            Pauli::Z => self.z(target),
+        }
+    }
+
+    /// Relabel the qubits so that qubit `j` becomes qubit `perm[j]`, without running any gates.
+    ///
+    /// This permutes the columns of the tableau and leaves the generators in place,
+    /// exactly as the equivalent SWAP gates would.
+    ///
+    /// Returns an error, leaving this state unchanged, if `perm` is not a permutation of `0..n`.
+    ///
+    /// ```
+    /// use circus::{Error, State};
+    ///
+    /// let mut state = State::new(3);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    /// state.p(2);
+    ///
+    /// let mut swapped = state.clone();
+    /// swapped.permute(&[1, 0, 2]).unwrap();
+    ///
+    /// let mut expected = state.clone();
+    /// expected.cx(0, 1);
+    /// expected.cx(1, 0);
+    /// expected.cx(0, 1);
+    /// assert!(swapped == expected);
+    ///
+    /// let mut permuted = state.clone();
+    /// permuted.permute(&[2, 0, 1]).unwrap();
+    /// permuted.permute(&[1, 2, 0]).unwrap();
+    /// assert!(permuted == state);
+    ///
+    /// assert_eq!(
+    ///     permuted.permute(&[0, 1]),
+    ///     Err(Error::DimensionMismatch { expected: 3, found: 2 })
+    /// );
+    /// assert_eq!(
+    ///     permuted.permute(&[0, 2, 2]),
+    ///     Err(Error::RepeatedQubit { qubit: 2 })
+    /// );
+    /// ```
+    pub fn permute(&mut self, perm: &[usize]) -> Result<(), Error> {
+        let n = self.n;
+        if perm.len() != n {
+            return Err(Error::DimensionMismatch {
+                expected: n,
+                found: perm.len(),
+            });
+        }
+        self.check_distinct(perm)?;
+
+        let mut row = vec![W::ZERO; 2 * self.words];
+        for i in 0..2 * n + 1 {
+            row.fill(W::ZERO);
+            for (j, &to) in perm.iter().enumerate() {
+                let (j5, pw) = W::locate(j);
+                let (to5, to_pw) = W::locate(to);
+                if self.x_word(i, j5) & pw != W::ZERO {
+                    row[2 * to5] |= to_pw;
+                }
+                if self.z_word(i, j5) & pw != W::ZERO {
+                    row[2 * to5 + 1] |= to_pw;
+                }
             }
+            self.xz[i].copy_from_slice(&row);
+        }
+        Ok(())
+    }
 
-            if self.r[p + self.n] > 0 {
-                Measurement::random(true)
-            } else {
-                Measurement::random(false)
+    /// Combine this state with `other` into a state on `self.n + other.n` qubits,
+    /// where the qubits of `other` follow those of this state.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// assert!(State::new(1).tensor(&State::new(1)) == State::new(2));
+    ///
+    /// let mut bell = State::new(2);
+    /// bell.h(0);
+    /// bell.cx(0, 1);
+    ///
+    /// let state = bell.tensor(&bell);
+    /// let stabilizers: Vec<_> = state.iter_stabilizers().map(|p| p.to_string()).collect();
+    /// assert_eq!(stabilizers, ["+XXII", "+ZZII", "+IIXX", "+IIZZ"]);
+    /// ```
+    pub fn tensor(&self, other: &Self) -> Self {
+        let n = self.n + other.n;
+        let mut state = Self::packed(n);
+
+        for (part, offset) in [(self, 0), (other, self.n)] {
+            for i in 0..part.n {
+                for (from, to) in [(i, i + offset), (i + part.n, i + offset + n)] {
+                    state.xz[to].fill(W::ZERO);
+                    state.r[to] = part.r[from];
+                    for j in 0..part.n {
+                        let (j5, pw) = W::locate(j);
+                        let (k5, pwk) = W::locate(j + offset);
+                        if part.x_word(from, j5) & pw != W::ZERO {
+                            *state.x_word_mut(to, k5) |= pwk;
+                        }
+                        if part.z_word(from, j5) & pw != W::ZERO {
+                            *state.z_word_mut(to, k5) |= pwk;
+                        }
+                    }
+                }
             }
+        }
+
+        state
+    }
+
+    /// Measure the `target` qubit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state. Use [`State::try_measure`] to get an error instead.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut bell = State::new(2);
+    /// bell.h(0);
+    /// bell.cx(0, 1);
+    ///
+    /// let mut ones = 0;
+    /// for _ in 0..1000 {
+    ///     let mut state = bell.clone();
+    ///     let a = state.measure(0);
+    ///     let b = state.measure(1);
+    ///     assert!(a.is_random() && !b.is_random());
+    ///     assert_eq!(a.is_one(), b.is_one());
+    ///     ones += a.is_one() as usize;
+    /// }
+    /// assert!((400..600).contains(&ones));
+    /// ```
+    pub fn measure(&mut self, target: usize) -> Measurement {
+        self.assert_qubit(target);
+        if let Some(p) = self.random_pivot(target) {
+            let bit = self.random_bit(); // moment of quantum randomness
+            self.collapse_to(p, target, bit);
+            Measurement::random(bit)
         } else {
-            let mut m = 0;
-            for a in 0..self.n {
-                // Before we were checking if stabilizer generators commute
-                if self.x[a][b5] & pw > 0 {
-                    // with Z_b; now we're checking destabilizer generators
-                    break;
+            Measurement::fixed(self.deterministic_outcome(target))
+        }
+    }
+
+    /// Measure the `target` qubit, drawing a random outcome from `rng` instead of the state's RNG.
+    ///
+    /// This is useful for replaying recorded randomness, such as bits from a hardware source,
+    /// for one measurement while the rest use the default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state.
+    ///
+    /// ```
+    /// use circus::State;
+    /// use rand::rngs::mock::StepRng;
+    ///
+    /// let mut zeros = StepRng::new(0, 0);
+    /// for _ in 0..10 {
+    ///     let mut state = State::new(2);
+    ///     state.h(0);
+    ///     state.cx(0, 1);
+    ///
+    ///     let m = state.measure_with(0, &mut zeros);
+    ///     assert!(m.is_random() && m.is_zero());
+    ///     assert_eq!(state.measure(1), false);
+    /// }
+    /// ```
+    pub fn measure_with<R: Rng>(&mut self, target: usize, rng: &mut R) -> Measurement {
+        self.assert_qubit(target);
+        if let Some(p) = self.random_pivot(target) {
+            let bit = rng.gen();
+            self.collapse_to(p, target, bit);
+            Measurement::random(bit)
+        } else {
+            Measurement::fixed(self.deterministic_outcome(target))
+        }
+    }
+
+    /// Sample a measurement of the `target` qubit without collapsing the state.
+    ///
+    /// A random outcome still consumes the RNG like [`State::measure`], but the tableau is left as it was,
+    /// so repeated peeks estimate outcome probabilities without re-running the circuit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    /// let before = state.clone();
+    ///
+    /// let ones = (0..1000).filter(|_| state.peek(0).is_one()).count();
+    /// assert!((400..600).contains(&ones));
+    /// assert_eq!(state, before);
+    ///
+    /// // Qubit 1 is still random, as if qubit 0 was never measured
+    /// assert!(state.measure(1).is_random());
+    /// assert!(!state.peek(0).is_random());
+    /// ```
+    pub fn peek(&mut self, target: usize) -> Measurement {
+        self.assert_qubit(target);
+        if self.random_pivot(target).is_some() {
+            Measurement::random(self.random_bit())
+        } else {
+            Measurement::fixed(self.deterministic_outcome(target))
+        }
+    }
+
+    /// Probability that measuring the `target` qubit gives `1`, without collapsing the state.
+    ///
+    /// This is `0.5` if the outcome is random, and otherwise `0.0` or `1.0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::from_bits(&[false, true, false]);
+    /// state.h(2);
+    /// assert_eq!(state.prob_one(0), 0.);
+    /// assert_eq!(state.prob_one(1), 1.);
+    /// assert_eq!(state.prob_one(2), 0.5);
+    /// ```
+    pub fn prob_one(&mut self, target: usize) -> f64 {
+        self.assert_qubit(target);
+        if self.random_pivot(target).is_some() {
+            0.5
+        } else if self.deterministic_outcome(target) {
+            1.
+        } else {
+            0.
+        }
+    }
+
+    /// [`State::prob_one`] for every qubit, from one elimination of the stabilizer generators.
+    ///
+    /// ```
+    /// use circus::{prep, State};
+    ///
+    /// assert_eq!(State::new(3).marginals(), [0.; 3]);
+    ///
+    /// let mut ghz = State::new(4);
+    /// ghz.run(prep::ghz(4)).for_each(drop);
+    /// assert_eq!(ghz.marginals(), [0.5; 4]);
+    ///
+    /// ghz.measure(0);
+    /// let marginals = ghz.marginals();
+    /// assert!(marginals == [0.; 4] || marginals == [1.; 4]);
+    /// ```
+    pub fn marginals(&self) -> Vec<f64> {
+        self.z_readout()
+            .into_iter()
+            .map(|bit| bit.map_or(0.5, |bit| bit as u8 as f64))
+            .collect()
+    }
+
+    /// The bits `b` if this is the computational basis state `|b_0 b_1 ...>`, where every measurement is determined,
+    /// or `None` if any qubit is in superposition.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut bell = State::new(2);
+    /// bell.h(0);
+    /// bell.cx(0, 1);
+    /// assert_eq!(bell.is_computational_basis(), None);
+    ///
+    /// let m = bell.measure_all();
+    /// let bits = vec![m[0].is_one(); 2];
+    /// assert_eq!(bell.is_computational_basis(), Some(bits));
+    ///
+    /// let state = State::from_bits(&[true, false, true]);
+    /// assert_eq!(state.is_computational_basis(), Some(vec![true, false, true]));
+    /// ```
+    pub fn is_computational_basis(&self) -> Option<Vec<bool>> {
+        self.z_readout().into_iter().collect()
+    }
+
+    /// Measure the `target` qubit in the eigenbasis of the Pauli `basis`,
+    /// where a `1` is the `-1` eigenvalue and the qubit is left in the measured eigenstate.
+    ///
+    /// ```
+    /// use circus::{pauli::PauliBasis, State};
+    ///
+    /// // |1>, |->, and |-i>, the -1 eigenstates of Z, X and Y
+    /// let mut state = State::from_bits(&[true, true, true]);
+    /// state.h(1);
+    /// state.h(2);
+    /// state.s(2);
+    ///
+    /// for _ in 0..2 {
+    ///     for (target, basis) in [(0, PauliBasis::Z), (1, PauliBasis::X), (2, PauliBasis::Y)] {
+    ///         let m = state.measure_basis(target, basis);
+    ///         assert!(m.is_one() && !m.is_random());
+    ///     }
+    /// }
+    ///
+    /// assert!(state.measure_basis(0, PauliBasis::X).is_random());
+    /// assert!(state.measure_basis(1, PauliBasis::Y).is_random());
+    /// assert!(state.measure_basis(2, PauliBasis::Z).is_random());
+    /// ```
+    pub fn measure_basis(&mut self, target: usize, basis: PauliBasis) -> Measurement {
+        // Rotate the basis onto Z, measure, and rotate back
+        match basis {
+            PauliBasis::X => {
+                self.h(target);
+                let measurement = self.measure(target);
+                self.h(target);
+                measurement
+            }
+            PauliBasis::Y => {
+                self.s_dag(target);
+                self.h(target);
+                let measurement = self.measure(target);
+                self.h(target);
+                self.s(target);
+                measurement
+            }
+            PauliBasis::Z => self.measure(target),
+        }
+    }
+
+    /// Measure the `target` qubit and return the outcome bit, without whether it was random.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::from_bits(&[true, false]);
+    /// state.h(1);
+    /// assert_eq!(state.clone().measure_bit(0), state.clone().measure(0).bit());
+    ///
+    /// let outcome = state.measure_bit(1);
+    /// assert_eq!(state.measure(1).bit(), outcome);
+    /// ```
+    pub fn measure_bit(&mut self, target: usize) -> bool {
+        self.measure(target).bit()
+    }
+
+    /// Measure the `target` qubit, first checking that it is a qubit of this state.
+    ///
+    /// ```
+    /// use circus::{Error, State};
+    ///
+    /// let mut state = State::new(1);
+    /// assert!(state.try_measure(0).unwrap().is_zero());
+    /// assert_eq!(state.try_measure(1).err(), Some(Error::QubitOutOfRange { qubit: 1, n: 1 }));
+    /// ```
+    pub fn try_measure(&mut self, target: usize) -> Result<Measurement, Error> {
+        self.check_distinct(&[target])?;
+        Ok(self.measure(target))
+    }
+
+    /// Measure the `target` qubit into bit `bit` of a classical `register`, like QASM's `measure q[i] -> c[j]`.
+    ///
+    /// The bit is overwritten, so a register bit reused by mid-circuit measurements holds the latest outcome.
+    ///
+    /// This returns [`Error::QubitOutOfRange`] or [`Error::BitOutOfRange`] without measuring
+    /// if `target` isn't a qubit of this state or `bit` is past the end of `register`.
+    ///
+    /// ```
+    /// use circus::{Error, State};
+    ///
+    /// let mut state = State::new(2);
+    /// let mut creg = [false; 2];
+    ///
+    /// state.x(0);
+    /// state.measure_into(0, &mut creg, 1).unwrap();
+    /// assert_eq!(creg, [false, true]);
+    ///
+    /// state.measure_into(1, &mut creg, 1).unwrap();
+    /// assert_eq!(creg, [false, false]);
+    ///
+    /// assert_eq!(
+    ///     state.measure_into(0, &mut creg, 2),
+    ///     Err(Error::BitOutOfRange { bit: 2, len: 2 })
+    /// );
+    /// assert_eq!(
+    ///     state.measure_into(2, &mut creg, 0),
+    ///     Err(Error::QubitOutOfRange { qubit: 2, n: 2 })
+    /// );
+    /// ```
+    pub fn measure_into(
+        &mut self,
+        target: usize,
+        register: &mut [bool],
+        bit: usize,
+    ) -> Result<Measurement, Error> {
+        self.check_distinct(&[target])?;
+        if bit >= register.len() {
+            return Err(Error::BitOutOfRange {
+                bit,
+                len: register.len(),
+            });
+        }
+        let measurement = self.measure(target);
+        register[bit] = measurement.bit();
+        Ok(measurement)
+    }
+
+    /// Measure the `target` qubit, resolving a random outcome to `0` without using the RNG.
+    ///
+    /// This collapses to a canonical representative state, which is useful for comparing against other simulators.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// for _ in 0..10 {
+    ///     let mut state = State::new(2);
+    ///     state.h(0);
+    ///     state.cx(0, 1);
+    ///
+    ///     let a = state.measure_deterministic_zero(0);
+    ///     let b = state.measure_deterministic_zero(1);
+    ///     assert!(a.is_random() && a.is_zero());
+    ///     assert!(!b.is_random() && b.is_zero());
+    /// }
+    /// ```
+    pub fn measure_deterministic_zero(&mut self, target: usize) -> Measurement {
+        self.assert_qubit(target);
+        if let Some(p) = self.random_pivot(target) {
+            self.collapse_to(p, target, false);
+            Measurement::random(false)
+        } else {
+            Measurement::fixed(self.deterministic_outcome(target))
+        }
+    }
+
+    /// Measure the `target` qubit for its effect on the state alone, discarding the outcome.
+    ///
+    /// A random outcome is resolved to `0` without using the RNG, like [`State::measure_deterministic_zero`],
+    /// and a determined one isn't computed at all since measuring it leaves the state unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of this state.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(3);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    /// state.cx(1, 2);
+    ///
+    /// state.collapse(1);
+    /// for target in 0..3 {
+    ///     let m = state.measure(target);
+    ///     assert!(!m.is_random() && m.is_zero());
+    /// }
+    /// ```
+    pub fn collapse(&mut self, target: usize) {
+        self.assert_qubit(target);
+        if let Some(p) = self.random_pivot(target) {
+            self.collapse_to(p, target, false);
+        }
+    }
+
+    /// Measure the `target` qubit, returning `(bit, was_random)`.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(2);
+    /// assert_eq!(state.measure_detailed(0), (false, false));
+    ///
+    /// state.h(0);
+    /// state.cx(0, 1);
+    /// let (bit, was_random) = state.measure_detailed(0);
+    /// assert!(was_random);
+    /// assert_eq!(state.measure_detailed(1), (bit, false));
+    /// ```
+    pub fn measure_detailed(&mut self, target: usize) -> (bool, bool) {
+        let measurement = self.measure(target);
+        (measurement.bit(), measurement.is_random())
+    }
+
+    /// Run a circuit along a single branch, forcing its measurements to the given `outcomes` in order,
+    /// and return the probability of that branch.
+    ///
+    /// Each random measurement multiplies the probability by 0.5 and each deterministic one by 1.0.
+    /// An [`Error::ImpossibleOutcome`] is returned if a deterministic measurement disagrees with its outcome,
+    /// and an [`Error::MissingOutcome`] if the circuit has more measurements than `outcomes`,
+    /// leaving this state as it was at that measurement. Any extra outcomes are ignored.
+    ///
+    /// ```
+    /// use circus::{
+    ///     gate::{Gates, HadamardGate},
+    ///     prep, Instruction, State,
+    /// };
+    ///
+    /// let mut circuit = prep::ghz(2);
+    /// circuit.push(Instruction::Measure { target: 0 });
+    /// circuit.push(Instruction::Measure { target: 1 });
+    /// circuit.push(Instruction::Gate(Gates::Hadamard(HadamardGate { target: 0 })));
+    /// circuit.push(Instruction::Measure { target: 0 });
+    ///
+    /// let mut total = 0.;
+    /// for branch in 0..8 {
+    ///     let outcomes: Vec<_> = (0..3).map(|i| branch >> i & 1 == 1).collect();
+    ///     let mut state = State::new(2);
+    ///     if let Ok(p) = state.run_branch(circuit.clone(), &outcomes) {
+    ///         total += p;
+    ///     }
+    /// }
+    /// assert_eq!(total, 1.);
+    /// ```
+    pub fn run_branch<I>(&mut self, instructions: I, outcomes: &[bool]) -> Result<f64, Error>
+    where
+        I: IntoIterator<Item = Instruction>,
+    {
+        let missing = Error::MissingOutcome {
+            measurement: outcomes.len(),
+        };
+        let mut outcomes = outcomes.iter().enumerate();
+        let mut probability = 1.;
+
+        for instruction in instructions {
+            match instruction {
+                Instruction::Gate(gate) => gate.apply(self),
+                Instruction::GateBroadcast(gates) => gates.iter().for_each(|gate| gate.apply(self)),
+                Instruction::Measure { target } => {
+                    let (measurement, &bit) = outcomes.next().ok_or(missing)?;
+
+                    if let Some(p) = self.random_pivot(target) {
+                        self.collapse_to(p, target, bit);
+                        probability *= 0.5;
+                    } else if self.deterministic_outcome(target) != bit {
+                        return Err(Error::ImpossibleOutcome { measurement });
+                    }
                 }
-                m += 1;
+                Instruction::Detector { .. } => {}
             }
-            self.rowcopy(2 * self.n, m + self.n);
-            for i in (m + 1)..self.n {
-                if self.x[i][b5] & pw > 0 {
-                    self.rowmult(2 * self.n, i + self.n);
+        }
+
+        Ok(probability)
+    }
+
+    /// Measure the mutually commuting `targets` in order, sharing one elimination of the stabilizer generators.
+    ///
+    /// The first deterministic target reduces the generators to row-echelon form, which gives every
+    /// deterministic outcome at once, so the deterministic targets after it are a lookup.
+    /// Random targets are measured with [`State::measure`]. Their outcomes leave the looked-up ones valid,
+    /// since those are outcomes of measurements that commute with them, so the elimination is never redone.
+    ///
+    /// The targets must commute with one another for this to hold. That is always true of the
+    /// single-qubit `Z` measurements made here, including repeated targets.
+    ///
+    /// This is faster than measuring each target with [`State::measure`] when most of them are deterministic,
+    /// such as reading out every qubit of a scrambled state that was already measured once.
+    /// It is slower when most targets are random, or when many generators have X parts to eliminate
+    /// and the rows are sparse enough for `measure` to be cheap, such as the syndrome ancillas of a surface code.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a target isn't a qubit of this state.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// // Three-qubit repetition code with a bit-flip on qubit 1 and two syndrome ancillas
+    /// fn prepare() -> State {
+    ///     let mut state = State::new(5);
+    ///     state.h(0);
+    ///     state.cx(0, 1);
+    ///     state.cx(0, 2);
+    ///
+    ///     state.h(1);
+    ///     state.p(1);
+    ///     state.p(1);
+    ///     state.h(1);
+    ///
+    ///     state.cx(0, 3);
+    ///     state.cx(1, 3);
+    ///     state.cx(1, 4);
+    ///     state.cx(2, 4);
+    ///     state
+    /// }
+    ///
+    /// let syndrome = prepare().measure_commuting(&[3, 4]);
+    ///
+    /// let mut state = prepare();
+    /// let expected = [state.measure(3), state.measure(4)];
+    ///
+    /// for (m, e) in syndrome.iter().zip(&expected) {
+    ///     assert!(m.is_one() && !m.is_random());
+    ///     assert_eq!(m.is_one(), e.is_one());
+    ///     assert_eq!(m.is_random(), e.is_random());
+    /// }
+    /// ```
+    ///
+    /// In a GHZ state the first outcome is random, and it fixes the others:
+    ///
+    /// ```
+    /// use circus::{prep, State};
+    ///
+    /// let mut ghz = State::new(4);
+    /// ghz.run(prep::ghz(4)).for_each(drop);
+    ///
+    /// let m = ghz.measure_commuting(&[2, 0, 3, 2, 1]);
+    /// assert!(m[0].is_random());
+    /// assert!(m[1..].iter().all(|m| !m.is_random() && m.is_one() == ghz.measure(0).is_one()));
+    /// ```
+    pub fn measure_commuting(&mut self, targets: &[usize]) -> Vec<Measurement> {
+        let mut session = self.measurement_session();
+        targets
+            .iter()
+            .map(|&target| session.measure(target))
+            .collect()
+    }
+
+    /// Start a [`MeasurementSession`], which reads out deterministic measurements from a cached
+    /// elimination of the stabilizer generators.
+    pub(crate) fn measurement_session(&mut self) -> MeasurementSession<'_, W> {
+        MeasurementSession {
+            state: self,
+            readout: None,
+        }
+    }
+
+    /// Measure every qubit, from qubit 0 to `n - 1`.
+    pub fn measure_all(&mut self) -> Vec<Measurement> {
+        (0..self.n).map(|target| self.measure(target)).collect()
+    }
+
+    /// Measure every qubit like [`State::measure_all`], packing the outcomes into an integer
+    /// with qubit `i` in bit `i`, or return `None` without measuring if there are more than 64 qubits.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let bits = [true, false, true, true, false];
+    /// let mut state = State::from_bits(&bits);
+    /// assert_eq!(state.measure_all_bits(), Some(0b01101));
+    ///
+    /// let mut bell = State::new(2);
+    /// bell.h(0);
+    /// bell.cx(0, 1);
+    /// assert!(matches!(bell.measure_all_bits(), Some(0b00 | 0b11)));
+    ///
+    /// assert_eq!(State::new(65).measure_all_bits(), None);
+    /// ```
+    pub fn measure_all_bits(&mut self) -> Option<u64> {
+        if self.n > 64 {
+            return None;
+        }
+        let bits = self
+            .measure_all()
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (i, m)| bits | (m.is_one() as u64) << i);
+        Some(bits)
+    }
+
+    /// Calculate the number of nonzero basis states (in 2^n) without modifying this state.
+    ///
+    /// Unlike [`State::nonzero`], which reorders the generators in place,
+    /// this performs the Gaussian elimination on a copy of the tableau.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    /// let before = state.to_string();
+    ///
+    /// assert_eq!(state.rank(), 1);
+    /// assert_eq!(state.to_string(), before);
+    /// ```
+    pub fn rank(&self) -> usize {
+        self.clone().nonzero()
+    }
+
+    /// Entanglement entropy in bits of the reduced state on `qubits` with the rest of the system.
+    ///
+    /// This is the rank over GF(2) of the stabilizer generators restricted to `qubits`, minus their number.
+    /// A qubit listed more than once is only counted once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of `qubits` isn't a qubit of this state.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(3);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    ///
+    /// assert_eq!(state.entanglement_entropy(&[0]), 1);
+    /// assert_eq!(state.entanglement_entropy(&[0, 1]), 0);
+    /// assert_eq!(state.entanglement_entropy(&[2]), 0);
+    /// assert_eq!(state.entanglement_entropy(&[0, 0]), 1);
+    /// assert_eq!(state.entanglement_entropy(&[1, 0, 1]), 0);
+    /// ```
+    pub fn entanglement_entropy(&self, qubits: &[usize]) -> usize {
+        qubits.iter().for_each(|&qubit| self.assert_qubit(qubit));
+        let mut qubits = qubits.to_vec();
+        qubits.sort_unstable();
+        qubits.dedup();
+        self.stabilizer_rank(&qubits) - qubits.len()
+    }
+
+    /// Number of logical qubits left unconstrained by the stabilizer generators,
+    /// which is `n` minus the rank over GF(2) of the stabilizer matrix.
+    ///
+    /// A pure state has `n` independent generators, so this is always 0 for a [`State`].
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// assert_eq!(State::new(1).logical_dimension(), 0);
+    ///
+    /// let mut bell = State::new(2);
+    /// bell.h(0);
+    /// bell.cx(0, 1);
+    /// bell.measure(0);
+    /// assert_eq!(bell.logical_dimension(), 0);
+    /// ```
+    pub fn logical_dimension(&self) -> usize {
+        let qubits: Vec<_> = (0..self.n).collect();
+        self.n - self.stabilizer_rank(&qubits)
+    }
+
+    /// Rank over GF(2) of the stabilizer generators restricted to `qubits`.
+    fn stabilizer_rank(&self, qubits: &[usize]) -> usize {
+        let width = 2 * qubits.len();
+        let words = (width >> 6) + 1;
+        let mut rows: Vec<Vec<u64>> = (self.n..2 * self.n)
+            .map(|i| {
+                let mut row = vec![0; words];
+                for (c, &j) in qubits.iter().enumerate() {
+                    let (j5, pw) = W::locate(j);
+                    if self.x_word(i, j5) & pw != W::ZERO {
+                        row[(2 * c) >> 6] |= 1 << ((2 * c) & 63);
+                    }
+                    if self.z_word(i, j5) & pw != W::ZERO {
+                        row[(2 * c + 1) >> 6] |= 1 << ((2 * c + 1) & 63);
+                    }
                 }
-            }
+                row
+            })
+            .collect();
 
-            if self.r[2 * self.n] > 0 {
-                Measurement::fixed(true)
-            } else {
-                Measurement::fixed(false)
+        let mut rank = 0;
+        for col in 0..width {
+            let (w, bit) = (col >> 6, 1 << (col & 63));
+            if let Some(k) = (rank..rows.len()).find(|&k| rows[k][w] & bit != 0) {
+                rows.swap(rank, k);
+                for k2 in 0..rows.len() {
+                    if k2 != rank && rows[k2][w] & bit != 0 {
+                        let (row, pivot) = row_pair(&mut rows, k2, rank);
+                        xor_assign(row, pivot);
+                    }
+                }
+                rank += 1;
             }
         }
+        rank
+    }
+
+    /// Returns `true` if the reduced state on qubits `a` and `b` is not a product of their single-qubit states.
+    ///
+    /// This checks for a nonzero mutual information `S(a) + S(b) - S(ab)`, so it also reports
+    /// the classical correlations left between two qubits of a larger entangled state such as GHZ.
+    /// A qubit is never entangled with itself, so this returns `false` if `a == b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` isn't a qubit of this state.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    /// assert!(state.are_entangled(0, 1));
+    /// assert!(!state.are_entangled(0, 0));
+    ///
+    /// state.measure(0);
+    /// assert!(!state.are_entangled(0, 1));
+    /// ```
+    pub fn are_entangled(&self, a: usize, b: usize) -> bool {
+        self.assert_qubit(a);
+        self.assert_qubit(b);
+        a != b
+            && self.entanglement_entropy(&[a]) + self.entanglement_entropy(&[b])
+                > self.entanglement_entropy(&[a, b])
     }
 
     /// Perform Gaussian elimination and calculate the number of nonzero basis states (in 2^n).
     pub fn nonzero(&mut self) -> usize {
         let mut i = self.n;
-        let mut k = i;
         for j in 0..self.n {
-            let j5 = j >> 5;
-            let pw = PW[j & 31];
+            let (j5, pw) = W::locate(j);
+            let mut k = i;
             for a in i..2 * self.n {
                 // Find a generator containing X in jth column
-                if (self.x[a][j5] & pw) > 0 {
+                if (self.x_word(a, j5) & pw) != W::ZERO {
                     break;
                 }
                 k += 1;
@@ -167,7 +2214,7 @@ impl State {
                 self.rowswap(i, k);
                 self.rowswap(i - self.n, k - self.n);
                 for k2 in (i + 1)..2 * self.n {
-                    if (self.x[k2][j5] & pw) > 0 {
+                    if (self.x_word(k2, j5) & pw) != W::ZERO {
                         // Gaussian elimination step
                         self.rowmult(k2, i);
                         self.rowmult(i - self.n, k2 - self.n);
@@ -181,12 +2228,11 @@ impl State {
         let g = i - self.n;
 
         for j in 0..self.n {
-            let j5 = j >> 5;
-            let pw = PW[j & 31];
+            let (j5, pw) = W::locate(j);
             let mut k = i;
             for a in i..2 * self.n {
                 // Find a generator containing Z in jth column
-                if (self.z[a][j5] & pw) > 0 {
+                if (self.z_word(a, j5) & pw) != W::ZERO {
                     break;
                 }
                 k += 1;
@@ -196,7 +2242,7 @@ impl State {
                 self.rowswap(i, k);
                 self.rowswap(i - self.n, k - self.n);
                 for k2 in (i + 1)..2 * self.n {
-                    if (self.z[k2][j5] & pw) > 0 {
+                    if (self.z_word(k2, j5) & pw) != W::ZERO {
                         self.rowmult(k2, i);
                         self.rowmult(i - self.n, k2 - self.n);
                     }
@@ -210,88 +2256,457 @@ impl State {
     }
 
     /// Format the current state as a string in bra-ket notation.
-    pub fn ket(&mut self) -> String {
+    ///
+    /// Each of the `2^g` nonzero basis states (see [`State::nonzero`]) is listed on its own line,
+    /// so this returns [`Error::TooManyBasisStates`] if `g` is more than [`MAX_KET_NONZERO`].
+    ///
+    /// ```
+    /// use circus::{prep, Error, State};
+    ///
+    /// let mut ghz = State::new(40);
+    /// ghz.run(prep::ghz(40)).for_each(drop);
+    /// assert_eq!(ghz.ket().unwrap().lines().count(), 2);
+    ///
+    /// let mut state = State::new(1);
+    /// state.x(0);
+    /// assert_eq!(state.ket().unwrap(), " +|1>\n");
+    ///
+    /// let mut state = State::new(2);
+    /// assert_eq!(state.ket().unwrap(), " +|00>\n");
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    /// assert_eq!(state.ket().unwrap(), " +|00>\n +|11>\n");
+    ///
+    /// let mut plus = State::new(20);
+    /// plus.h_all(&(0..20).collect::<Vec<_>>());
+    /// assert_eq!(
+    ///     plus.ket(),
+    ///     Err(Error::TooManyBasisStates {
+    ///         nonzero: 20,
+    ///         max: 16
+    ///     })
+    /// );
+    /// ```
+    ///
+    /// Each basis state is prefixed with its phase relative to the first, including `±i`:
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    /// state.p(1);
+    /// assert_eq!(state.ket().unwrap(), " +|00>\n+i|11>\n");
+    ///
+    /// state.s_dag(0);
+    /// state.s_dag(0);
+    /// assert_eq!(state.ket().unwrap(), " +|00>\n-i|11>\n");
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// state.h(1);
+    /// state.p(0);
+    /// state.p(1);
+    /// assert_eq!(state.ket().unwrap(), " +|00>\n+i|10>\n+i|01>\n -|11>\n");
+    ///
+    /// // HSH|0> is proportional to |0> - i|1>
+    /// let mut state = State::new(1);
+    /// state.h(0);
+    /// state.p(0);
+    /// state.h(0);
+    /// assert_eq!(state.ket().unwrap(), " +|0>\n-i|1>\n");
+    /// ```
+    pub fn ket(&mut self) -> Result<String, Error> {
+        let g = self.nonzero();
+        if g > MAX_KET_NONZERO {
+            return Err(Error::TooManyBasisStates {
+                nonzero: g,
+                max: MAX_KET_NONZERO,
+            });
+        }
+
+        let mut s = String::new();
+        self.for_each_basis_state(g, |state| state.ket_basis_state(&mut s));
+        Ok(s)
+    }
+
+    /// Print this state on one line as a sum of basis states with their amplitudes,
+    /// such as `0.7071 |00> + 0.7071 |11>` for a Bell state.
+    ///
+    /// Every amplitude has the magnitude `2^(-g/2)` (see [`State::nonzero`]), written to 4 decimal places,
+    /// with its sign and any factor of `i` relative to the first basis state.
+    /// Like [`State::ket`], this returns [`Error::TooManyBasisStates`] if `g` is more than [`MAX_KET_PRETTY_NONZERO`],
+    /// which is lower than [`MAX_KET_NONZERO`] to keep the line readable.
+    ///
+    /// ```
+    /// use circus::{Error, State};
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    /// assert_eq!(state.ket_pretty().unwrap(), "0.7071 |00> + 0.7071 |11>");
+    ///
+    /// state.p(1);
+    /// state.z(0);
+    /// assert_eq!(state.ket_pretty().unwrap(), "0.7071 |00> - 0.7071i |11>");
+    ///
+    /// assert_eq!(State::new(1).ket_pretty().unwrap(), "1.0000 |0>");
+    ///
+    /// let mut plus = State::new(12);
+    /// plus.h_all(&(0..12).collect::<Vec<_>>());
+    /// assert!(plus.ket().is_ok());
+    /// assert_eq!(
+    ///     plus.ket_pretty(),
+    ///     Err(Error::TooManyBasisStates {
+    ///         nonzero: 12,
+    ///         max: 10
+    ///     })
+    /// );
+    /// ```
+    pub fn ket_pretty(&mut self) -> Result<String, Error> {
         let g = self.nonzero();
+        if g > MAX_KET_PRETTY_NONZERO {
+            return Err(Error::TooManyBasisStates {
+                nonzero: g,
+                max: MAX_KET_PRETTY_NONZERO,
+            });
+        }
 
+        let magnitude = 0.5f64.powf(g as f64 / 2.);
         let mut s = String::new();
-        self.ket_basis_state(&mut s);
+        self.for_each_basis_state(g, |state| {
+            let phase = state.basis_phase();
+            let i = if phase % 2 == 1 { "i" } else { "" };
+            match (s.is_empty(), phase >= 2) {
+                (true, false) => {}
+                (true, true) => s.push('-'),
+                (false, false) => s.push_str(" + "),
+                (false, true) => s.push_str(" - "),
+            }
+            s.push_str(&format!("{magnitude:.4}{i} |"));
+            for j in 0..state.n {
+                let bit = state.x_bit(2 * state.n, j);
+                s.push(if bit { '1' } else { '0' });
+            }
+            s.push('>');
+        });
+        Ok(s)
+    }
+
+    /// Export the `2^n` amplitudes of this state, where bit `j` of each index is the value of qubit `j`.
+    ///
+    /// Each of the `2^g` nonzero basis states (see [`State::nonzero`]) has an amplitude of magnitude `2^(-g/2)`.
+    /// The tableau doesn't track a global phase, so one is chosen to give the first basis state
+    /// enumerated by [`State::ket`] a positive real amplitude.
+    /// That basis state always has the lowest index, so this matches simulators that normalize
+    /// the first nonzero amplitude to be positive and real, and equivalent circuits give equal vectors.
+    /// To compare against a simulator that tracks the global phase, divide its amplitudes by the phase of its first nonzero one.
+    /// This returns [`Error::TooManyQubits`] for more than [`MAX_STATEVECTOR_QUBITS`] qubits.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    ///
+    /// let amplitudes = state.to_statevector().unwrap();
+    /// let expected = [0.5f64.sqrt(), 0., 0., 0.5f64.sqrt()];
+    /// for (amplitude, expected) in amplitudes.iter().zip(expected) {
+    ///     assert!((amplitude.re - expected).abs() < 1e-12);
+    ///     assert_eq!(amplitude.im, 0.);
+    /// }
+    /// ```
+    ///
+    /// Here `|10> + i|01>` and `-i|10> + |01>` are prepared, differing only by a global phase:
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut a = State::from_bits(&[true, false]);
+    /// a.h(1);
+    /// a.p(1);
+    /// a.cx(1, 0);
+    ///
+    /// let mut b = State::from_bits(&[false, true]);
+    /// b.h(0);
+    /// b.s_dag(0);
+    /// b.cx(0, 1);
+    ///
+    /// let amplitudes = a.to_statevector().unwrap();
+    /// assert_eq!(amplitudes[1].re, 0.5f64.sqrt());
+    /// assert_eq!(amplitudes[2].im, 0.5f64.sqrt());
+    /// assert_eq!(amplitudes, b.to_statevector().unwrap());
+    /// ```
+    pub fn to_statevector(&mut self) -> Result<Vec<Complex64>, Error> {
+        if self.n > MAX_STATEVECTOR_QUBITS {
+            return Err(Error::TooManyQubits {
+                n: self.n,
+                max: MAX_STATEVECTOR_QUBITS,
+            });
+        }
+
+        let mut amplitudes = vec![Complex64::new(0., 0.); 1 << self.n];
+        let g = self.nonzero();
+        let magnitude = 0.5f64.powf(g as f64 / 2.);
+
+        self.for_each_basis_state(g, |state| {
+            let index = (0..state.n)
+                .filter(|&j| state.x_bit(2 * state.n, j))
+                .fold(0, |index, j| index | 1 << j);
+            amplitudes[index] = Complex64::i().powi(state.basis_phase()) * magnitude;
+        });
+
+        Ok(amplitudes)
+    }
+
+    /// Export the `2^n × 2^n` density matrix `|ψ⟩⟨ψ|` of this state as a list of rows,
+    /// using the same basis ordering as [`State::to_statevector`].
+    /// [`MixedState::to_density_matrix`] does the same for mixed states, such as after a partial trace.
+    ///
+    /// This returns [`Error::TooManyQubits`] for more than `n_max` qubits
+    /// (or more than [`MAX_STATEVECTOR_QUBITS`]).
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(1);
+    /// state.h(0);
+    ///
+    /// let rho = state.to_density_matrix(1).unwrap();
+    /// for row in &rho {
+    ///     for entry in row {
+    ///         assert!((entry.re - 0.5).abs() < 1e-12 && entry.im.abs() < 1e-12);
+    ///     }
+    /// }
+    ///
+    /// assert!(State::new(2).to_density_matrix(1).is_err());
+    /// ```
+    pub fn to_density_matrix(&mut self, n_max: usize) -> Result<Vec<Vec<Complex64>>, Error> {
+        if self.n > n_max {
+            return Err(Error::TooManyQubits {
+                n: self.n,
+                max: n_max,
+            });
+        }
+
+        let psi = self.to_statevector()?;
+        Ok(psi
+            .iter()
+            .map(|a| psi.iter().map(|b| a * b.conj()).collect())
+            .collect())
+    }
+
+    /// Returns `true` if this tableau describes a valid, normalized state.
+    ///
+    /// The stabilizer generators must have real phases and commute with each other, and the
+    /// amplitudes of the enumerated basis states must have squared magnitudes summing to 1.
+    /// That sum is only checked if `g` (see [`State::nonzero`]) is at most [`MAX_STATEVECTOR_QUBITS`].
+    /// A state built by this crate always passes, so this is for checking tableaux edited by hand or imported.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut bell = State::new(2);
+    /// bell.h(0);
+    /// bell.cx(0, 1);
+    /// assert!(bell.is_normalized());
+    ///
+    /// // Replace the stabilizer ZZ with ZI, which anticommutes with XX, in the z word of row 3
+    /// let mut bytes = bell.to_bytes();
+    /// bytes[16 + 3 * 16 + 8] &= !(1 << 1);
+    /// let mut edited = State::from_bytes(&bytes).unwrap();
+    /// assert!(!edited.is_normalized());
+    /// ```
+    pub fn is_normalized(&mut self) -> bool {
+        let n = self.n;
+        if (n..2 * n).any(|i| self.r[i] % 2 != 0) {
+            return false;
+        }
+        for i in n..2 * n {
+            if (i + 1..2 * n).any(|k| self.anticommutes(i, &self.xz[k])) {
+                return false;
+            }
+        }
+
+        let g = self.nonzero();
+        if g > MAX_STATEVECTOR_QUBITS {
+            return true;
+        }
+
+        // Sum amplitudes by basis state, so that a basis state enumerated twice is caught
+        let magnitude = 0.5f64.powf(g as f64 / 2.);
+        let mut amplitudes = HashMap::new();
+        self.for_each_basis_state(g, |state| {
+            let x: Vec<_> = (0..state.words).map(|j5| state.x_word(2 * n, j5)).collect();
+            *amplitudes.entry(x).or_insert(Complex64::new(0., 0.)) +=
+                Complex64::i().powi(state.basis_phase()) * magnitude;
+        });
+        let norm: f64 = amplitudes.values().map(Complex64::norm_sqr).sum();
+        (norm - 1.).abs() < 1e-9
+    }
+
+    /// Set the scratch row to each of the basis states with a nonzero amplitude in turn,
+    /// calling `f` after each one, after [`State::nonzero`] has returned `g`.
+    fn for_each_basis_state(&mut self, g: usize, mut f: impl FnMut(&Self)) {
+        self.seed(g);
+        f(self);
 
-        for t in 0..PW[g] - 1 {
+        for t in 0u64..(1 << g) - 1 {
             let t2 = t ^ (t + 1);
-            for i in 0..g {
-                if t2 & PW[i] > 0 {
-                    self.rowmult(2 * self.n, self.n + i);
-                }
+            for i in (0..g).filter(|i| t2 >> i & 1 == 1) {
+                self.rowmult(2 * self.n, self.n + i);
             }
-            self.ket_basis_state(&mut s);
+            f(self);
         }
+    }
 
-        s
+    /// Set the scratch row to a basis state with a nonzero amplitude,
+    /// after [`State::nonzero`] has returned `g`.
+    fn seed(&mut self, g: usize) {
+        let n = self.n;
+
+        self.r[2 * n] = 0;
+        self.xz[2 * n].fill(W::ZERO);
+
+        for i in (n + g..2 * n).rev() {
+            let mut f = self.r[i];
+            let mut min = 0;
+            for j in (0..n).rev() {
+                let (j5, pw) = W::locate(j);
+                if self.z_word(i, j5) & pw != W::ZERO {
+                    min = j;
+                    if self.x_word(2 * n, j5) & pw != W::ZERO {
+                        f = (f + 2) % 4;
+                    }
+                }
+            }
+
+            if f == 2 {
+                // Make the seed consistent with the ith equation
+                let (min5, pw) = W::locate(min);
+                *self.x_word_mut(2 * n, min5) ^= pw;
+            }
+        }
     }
 
+    /// Phase exponent of the product of rows `k` and `i`, including both rows' own phases.
+    ///
+    /// [`phase_exponent`] sums over every word of the rows, so qubits past the first word count like any other.
     fn clifford(&mut self, i: usize, k: usize) -> i32 {
-        let mut e = 0;
-
-        for j in 0..self.over32 {
-            for l in 0..PW.len() {
-                let pw = PW[l];
-                // X
-                if (self.x[k][j] & pw) > 0 && (!(self.z[k][j] & pw)) > 0 {
-                    if (self.x[i][j] & pw) > 0 && (self.z[i][j] & pw) > 0 {
-                        e += 1; // XY=iZ
-                    }
+        #[cfg(feature = "op-stats")]
+        {
+            self.op_stats.clifford += 1;
+        }
 
-                    if (!(self.x[i][j] & pw) > 0) && (self.z[i][j] & pw) > 0 {
-                        e -= 1; // XZ=-iY
-                    }
-                    if (self.x[k][j] & pw) > 0 && (self.z[k][j] & pw) > 0
-                    // Y
-                    {
-                        if (!(self.x[i][j] & pw) > 0) && (self.z[i][j] & pw) > 0 {
-                            e += 1; // YZ=iX
-                        }
+        let e = phase_exponent(&self.xz[i], &self.xz[k]);
+        (e + self.r[i] + self.r[k]).rem_euclid(4)
+    }
 
-                        if (self.x[i][j] & pw) > 0 && (!(self.z[i][j] & pw) > 0) {
-                            e -= 1; // YX=-iZ
-                        }
+    /// Deterministic Z outcomes for every qubit, or `None` where measuring would be random.
+    ///
+    /// This reduces a copy of the stabilizer generators to row-echelon form, first on the x bits
+    /// and then on the z bits of the remaining Z-only generators. `Z_b` is in the stabilizer group
+    /// exactly when one of the reduced Z-only rows is `±Z_b`, whose sign is the outcome.
+    fn z_readout(&self) -> Vec<Option<bool>> {
+        let n = self.n;
+        let w = 2 * self.words;
+        let mut rows = BinaryMatrix::<W>::new(n, w);
+        rows.data.copy_from_slice(&self.xz.data[n * w..2 * n * w]);
+        let mut r = self.r[n..2 * n].to_vec();
+        // Rows are swapped through this permutation rather than moved
+        let mut order: Vec<_> = (0..n).collect();
+
+        // Row products never set a bit in a column that no row has, so those columns can't hold a pivot
+        let columns = |rows: &BinaryMatrix<W>, order: &[usize], z: usize| {
+            let mut any = vec![W::ZERO; w / 2];
+            for &k in order {
+                for (any, xz) in any.iter_mut().zip(rows[k].chunks_exact(2)) {
+                    *any |= xz[z];
+                }
+            }
+            any
+        };
+
+        let mut i = 0;
+        let any_x = columns(&rows, &order, 0);
+        for j in 0..n {
+            let (j5, pw) = W::locate(j);
+            if any_x[j5] & pw == W::ZERO {
+                continue;
+            }
+            if let Some(k) = (i..n).find(|&k| rows[order[k]][2 * j5] & pw != W::ZERO) {
+                order.swap(i, k);
+                let p = order[i];
+                for &k2 in &order[i + 1..] {
+                    if rows[k2][2 * j5] & pw != W::ZERO {
+                        let (row, other) = rows.row_pair(k2, p);
+                        r[k2] = (phase_exponent(row, other) + r[k2] + r[p]).rem_euclid(4);
+                        xor_assign(row, other);
                     }
-                    if (!(self.x[k][j] & pw) > 0) && (self.z[k][j] & pw) > 0
-                    // Z
-                    {
-                        if (self.x[i][j] & pw) > 0 && (!(self.z[i][j] & pw) > 0) {
-                            e += 1; // ZX=iY
-                        }
+                }
+                i += 1;
+            }
+        }
 
-                        if (self.x[i][j] & pw) > 0 && (self.z[i][j] & pw) > 0 {
-                            e -= 1; // ZY=-iX
-                        }
+        // Rows i..n are now Z-only; reduce them so every pivot column is cleared in the others.
+        // Products of Z-only rows pick up no phase, so only their signs are combined.
+        let first = i;
+        let any_z = columns(&rows, &order[first..], 1);
+        let mut pivots = Vec::new();
+        for j in 0..n {
+            let (j5, pw) = W::locate(j);
+            if any_z[j5] & pw == W::ZERO {
+                continue;
+            }
+            if let Some(k) = (i..n).find(|&k| rows[order[k]][2 * j5 + 1] & pw != W::ZERO) {
+                order.swap(i, k);
+                let p = order[i];
+                for &k2 in &order[first..] {
+                    if k2 != p && rows[k2][2 * j5 + 1] & pw != W::ZERO {
+                        let (row, other) = rows.row_pair(k2, p);
+                        r[k2] = (r[k2] + r[p]) % 4;
+                        xor_assign(row, other);
                     }
                 }
+                pivots.push((p, j));
+                i += 1;
             }
         }
 
-        e = (e + self.r[i] + self.r[k]) % 4;
-        if e >= 0 {
-            e
-        } else {
-            e + 4
+        let mut readout = vec![None; n];
+        for (row, j) in pivots {
+            let weight: u32 = rows[row].iter().map(|word| word.count_ones()).sum();
+            if weight == 1 {
+                readout[j] = Some(r[row] == 2);
+            }
         }
+        readout
     }
 
-    fn ket_basis_state(&self, s: &mut String) {
+    /// Phase exponent of the basis state in the scratch row.
+    fn basis_phase(&self) -> i32 {
         let mut e = self.r[2 * self.n];
 
         for j in 0..self.n {
-            let j5 = j >> 5;
-            let pw = PW[j & 31];
+            let (j5, pw) = W::locate(j);
 
             // Pauli operator is "Y"
-            if (self.x[2 * self.n][j5] & pw) > 0 && (self.z[2 * self.n][j5] & pw) > 0 {
+            if (self.x_word(2 * self.n, j5) & pw) != W::ZERO
+                && (self.z_word(2 * self.n, j5) & pw) != W::ZERO
+            {
                 e = (e + 1) % 4;
             }
         }
 
-        match e {
+        e
+    }
+
+    fn ket_basis_state(&self, s: &mut String) {
+        match self.basis_phase() {
             0 => s.push_str(" +|"),
             1 => s.push_str("+i|"),
             2 => s.push_str(" -|"),
@@ -300,60 +2715,869 @@ impl State {
         }
 
         for j in 0..self.n {
-            let j5 = j >> 5;
-            let pw = PW[j & 31];
+            let (j5, pw) = W::locate(j);
 
-            if (self.x[2 * self.n][j5] & pw) > 0 {
-                s.push_str("1")
+            if (self.x_word(2 * self.n, j5) & pw) != W::ZERO {
+                s.push('1')
             } else {
-                s.push_str("0")
+                s.push('0')
             }
         }
 
         s.push_str(">\n");
     }
 
-    fn rowset(&mut self, i: usize, b: usize) {
-        for j in 0..self.over32 {
-            self.x[i][j] = 0;
-            self.z[i][j] = 0;
+    /// Find a stabilizer generator that anticommutes with `Z_target`,
+    /// in which case measuring `target` is random.
+    fn random_pivot(&self, target: usize) -> Option<usize> {
+        let (b5, pw) = W::locate(target);
+
+        // if a Zbar does NOT commute with Z_b (the operator being measured), then outcome is random
+        (0..self.n).find(|&p| self.x_word(p + self.n, b5) & pw != W::ZERO)
+    }
+
+    /// Collapse a random measurement of `target` to `bit`, given its anticommuting generator `p`.
+    fn collapse_to(&mut self, p: usize, target: usize, bit: bool) {
+        let (b5, pw) = W::locate(target);
+
+        self.rowcopy(p, p + self.n); // Set Xbar_p := Zbar_p
+        self.rowset(p + self.n, target + self.n); // Set Zbar_p := Z_b
+        self.r[p + self.n] = 2 * bit as i32;
+        for i in 0..2 * self.n {
+            // Now update the Xbar's and Zbar's that don't commute with
+            if (i != p) && (self.x_word(i, b5) & pw != W::ZERO) {
+                self.rowmult(i, p);
+            } // Z_b
+        }
+    }
+
+    /// One mask per word with the bits of the qubits in `range` set.
+    fn range_masks(&self, range: Range<usize>) -> Vec<W> {
+        assert!(range.end <= self.n, "qubit range out of bounds");
+        let mut masks = vec![W::ZERO; self.words];
+        for q in range {
+            let (q5, pw) = W::locate(q);
+            masks[q5] |= pw;
+        }
+        masks
+    }
+
+    /// Draw a random measurement outcome.
+    pub(crate) fn random_bit(&mut self) -> bool {
+        match &mut self.rng {
+            Some(rng) => rng.gen(),
+            None => rand::random(),
+        }
+    }
+
+    /// Returns `true` if row `i` anticommutes with the Pauli operator with interleaved words `xz`.
+    fn anticommutes(&self, i: usize, xz: &[W]) -> bool {
+        let parity = self.xz[i]
+            .chunks_exact(2)
+            .zip(xz.chunks_exact(2))
+            .fold(0, |parity, (row, p)| {
+                parity ^ ((row[0] & p[1]) ^ (row[1] & p[0])).count_ones()
+            });
+        parity & 1 == 1
+    }
+
+    /// Panic if `qubit` isn't a qubit of this state, rather than quietly touching the padding bits of a word.
+    fn assert_qubit(&self, qubit: usize) {
+        assert!(
+            qubit < self.n,
+            "qubit {qubit} is out of range for a state of {} qubits",
+            self.n
+        );
+    }
+
+    /// Check that `qubits` are distinct qubits of this state.
+    pub(crate) fn check_distinct(&self, qubits: &[usize]) -> Result<(), Error> {
+        let mut seen = vec![false; self.n];
+        for &qubit in qubits {
+            if qubit >= self.n {
+                return Err(Error::QubitOutOfRange { qubit, n: self.n });
+            }
+            if seen[qubit] {
+                return Err(Error::RepeatedQubit { qubit });
+            }
+            seen[qubit] = true;
+        }
+        Ok(())
+    }
+
+    /// Check that an operand acting on `n` qubits matches this state.
+    pub(crate) fn check_dimension(&self, n: usize) -> Result<(), Error> {
+        if n != self.n {
+            return Err(Error::DimensionMismatch {
+                expected: self.n,
+                found: n,
+            });
+        }
+        Ok(())
+    }
+
+    /// Compute the outcome of measuring `target`, which must not be random, using the scratch row.
+    fn deterministic_outcome(&mut self, target: usize) -> bool {
+        let (b5, pw) = W::locate(target);
+
+        // Before we were checking if stabilizer generators commute
+        // with Z_b; now we're checking destabilizer generators
+        let m = (0..self.n)
+            .find(|&m| self.x_word(m, b5) & pw != W::ZERO)
+            .unwrap_or(self.n);
+
+        self.rowcopy(2 * self.n, m + self.n);
+        for i in (m + 1)..self.n {
+            if self.x_word(i, b5) & pw != W::ZERO {
+                self.rowmult(2 * self.n, i + self.n);
+            }
         }
+
+        self.r[2 * self.n] > 0
+    }
+
+    fn rowset(&mut self, i: usize, b: usize) {
+        self.xz[i].fill(W::ZERO);
         self.r[i] = 0;
         if b < self.n {
-            let b5 = b >> 5;
-            let b31 = b & 31;
-            self.x[i][b5] = PW[b31];
+            let (b5, pw) = W::locate(b);
+            *self.x_word_mut(i, b5) = pw;
         } else {
-            let b5 = (b - self.n) >> 5;
-            let b31 = (b - self.n) & 31;
-            self.z[i][b5] = PW[b31];
+            let (b5, pw) = W::locate(b - self.n);
+            *self.z_word_mut(i, b5) = pw;
         }
     }
 
     fn rowcopy(&mut self, i: usize, k: usize) {
-        for j in 0..self.over32 {
-            self.x[i][j] = self.x[k][j];
-            self.z[i][j] = self.z[k][j];
+        #[cfg(feature = "op-stats")]
+        {
+            self.op_stats.rowcopy += 1;
+        }
+
+        if i != k {
+            let (row, other) = self.xz.row_pair(i, k);
+            row.copy_from_slice(other);
         }
         self.r[i] = self.r[k];
     }
 
     fn rowswap(&mut self, i: usize, k: usize) {
+        #[cfg(feature = "op-stats")]
+        {
+            self.op_stats.rowswap += 1;
+        }
+
         self.rowcopy(2 * self.n, k);
         self.rowcopy(k, i);
         self.rowcopy(i, 2 * self.n);
     }
 
     fn rowmult(&mut self, i: usize, k: usize) {
+        #[cfg(feature = "op-stats")]
+        {
+            self.op_stats.rowmult += 1;
+        }
+
         self.r[i] = self.clifford(i, k);
-        for j in 0..self.over32 {
-            self.x[i][j] ^= self.x[k][j];
-            self.z[i][j] ^= self.z[k][j];
+
+        let (row, other) = self.xz.row_pair(i, k);
+        xor_assign(row, other);
+    }
+}
+
+/// Operations on the rows as Pauli operators.
+///
+/// Borrowed rows are packed like the tableau, and owned [`PauliString`]s into `u64` words.
+impl<W: Word> State<W> {
+    /// Iterate over the stabilizer generators, borrowing each row of the tableau.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut state = State::new(2);
+    /// state.h(0);
+    /// state.cx(0, 1);
+    ///
+    /// assert_eq!(state.iter_stabilizers().count(), 2);
+    /// assert!(state.iter_stabilizers().any(|p| p.to_string() == "+XX"));
+    /// assert!(state.iter_stabilizers().map(|p| p.to_owned()).eq(state.stabilizers()));
+    /// ```
+    pub fn iter_stabilizers(&self) -> impl Iterator<Item = PauliStringRef<'_, W>> {
+        (self.n..2 * self.n).map(|i| self.row(i))
+    }
+
+    /// Copy the stabilizer generators.
+    pub fn stabilizers(&self) -> Vec<PauliString> {
+        self.iter_stabilizers().map(|p| p.to_owned()).collect()
+    }
+
+    /// Reduce the stabilizer generators to a form shared by every tableau describing this state.
+    ///
+    /// Unlike comparing tableaux, which also compares destabilizers and the order of the generators,
+    /// two states have equal canonical forms exactly when they are the same state up to global phase.
+    ///
+    /// ```
+    /// use circus::State;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut a = State::new(2);
+    /// a.h(0);
+    /// a.cx(0, 1);
+    ///
+    /// let mut b = State::new(2);
+    /// b.h(1);
+    /// b.cx(1, 0);
+    /// b.z(0);
+    /// b.z(1);
+    ///
+    /// assert_ne!(a, b);
+    /// assert_eq!(a.canonical(), b.canonical());
+    ///
+    /// let mut c = a.clone();
+    /// c.z(0);
+    /// let states: HashSet<_> = [&a, &b, &c].iter().map(|s| s.canonical()).collect();
+    /// assert_eq!(states.len(), 2);
+    ///
+    /// let generators: Vec<_> = a.canonical().stabilizers().iter().map(|p| p.to_string()).collect();
+    /// assert_eq!(generators, ["+XX", "+ZZ"]);
+    /// ```
+    pub fn canonical(&self) -> CanonicalState {
+        self.canonical_rows(self.n..2 * self.n)
+    }
+
+    /// Reduce the generators in tableau rows `generators` to reduced row echelon form.
+    pub(crate) fn canonical_rows(&self, generators: Range<usize>) -> CanonicalState {
+        let mut rows: Vec<_> = generators
+            .map(|i| Row {
+                xz: self.xz[i].into(),
+                r: self.r[i],
+            })
+            .collect();
+
+        // Reduced row echelon form over the columns x_0, z_0, x_1, z_1, ..., which is unique for the group
+        let mut rank = 0;
+        for c in 0..2 * self.n {
+            let j = c / 2;
+            let (j5, bit) = W::locate(j);
+            let w = 2 * j5 + c % 2;
+            let Some(k) = (rank..rows.len()).find(|&k| rows[k].xz[w] & bit != W::ZERO) else {
+                continue;
+            };
+            rows.swap(rank, k);
+            for k in 0..rows.len() {
+                if k != rank && rows[k].xz[w] & bit != W::ZERO {
+                    Row::mult(&mut rows, k, rank);
+                }
+            }
+            rank += 1;
+        }
+
+        let stabilizers = rows
+            .iter()
+            .map(|row| {
+                PauliStringRef {
+                    n: self.n,
+                    xz: &row.xz,
+                    r: row.r,
+                }
+                .to_owned()
+            })
+            .collect();
+        CanonicalState { stabilizers }
+    }
+
+    /// Conjugate this state by the Clifford operator mapping each `X_j` and `Z_j` to the rows of `matrix`,
+    /// with the phase exponents `signs`, for gates circus doesn't provide.
+    ///
+    /// See [`Clifford::from_symplectic`] for the errors returned, in which case this state is unchanged.
+    ///
+    /// ```
+    /// use circus::{clifford::SymplecticMatrix, Error, State};
+    ///
+    /// // CX maps X_0 to X_0 X_1 and Z_1 to Z_0 Z_1, fixing X_1 and Z_0
+    /// let mut cx = SymplecticMatrix::identity(2);
+    /// cx.set(0, 1, true);
+    /// cx.set(3, 2, true);
+    ///
+    /// let mut a = State::new(2);
+    /// a.h(0);
+    /// a.p(1);
+    /// let mut b = a.clone();
+    ///
+    /// a.apply_symplectic(&cx, &[0; 4]).unwrap();
+    /// b.cx(0, 1);
+    /// assert!(a == b);
+    ///
+    /// let mut not_symplectic = SymplecticMatrix::identity(2);
+    /// not_symplectic.set(0, 1, true);
+    /// assert_eq!(
+    ///     a.apply_symplectic(&not_symplectic, &[0; 4]),
+    ///     Err(Error::NotSymplectic)
+    /// );
+    /// ```
+    pub fn apply_symplectic(
+        &mut self,
+        matrix: &SymplecticMatrix,
+        signs: &[i32],
+    ) -> Result<(), Error> {
+        Clifford::from_symplectic(matrix, signs)?.apply_to(self)
+    }
+
+    /// Convert this pure state into a [`MixedState`] with all `n` of its stabilizer generators.
+    pub fn into_mixed(self) -> MixedState<W> {
+        MixedState::from(self)
+    }
+
+    /// Encode the tableau as bytes, for checkpointing a state and reloading it with [`State::from_bytes`].
+    ///
+    /// The layout is `n` and [`State::words`] as little-endian `u64`s, then the interleaved x and z words
+    /// of all `2n + 1` rows as little-endian words of type `W`, then one byte for each row's phase exponent `r`.
+    /// The bytes can only be decoded into a state with the same word type.
+    ///
+    /// ```
+    /// use circus::{Error, State};
+    /// use rand::{rngs::StdRng, Rng, SeedableRng};
+    ///
+    /// let mut rng = StdRng::seed_from_u64(7);
+    /// let mut state = State::new(40);
+    /// for _ in 0..500 {
+    ///     let (a, b) = (rng.gen_range(0..40), rng.gen_range(0..40));
+    ///     match rng.gen_range(0..3) {
+    ///         0 => state.h(a),
+    ///         1 => state.p(a),
+    ///         _ if a != b => state.cx(a, b),
+    ///         _ => state.x(a),
+    ///     }
+    /// }
+    ///
+    /// let bytes = state.to_bytes();
+    /// assert!(State::from_bytes(&bytes).unwrap() == state);
+    /// assert_eq!(
+    ///     State::from_bytes(&bytes[1..]).err(),
+    ///     Some(Error::InvalidBytes)
+    /// );
+    ///
+    /// // Bits past the last qubit of a word, here qubit 40 of the first row's x word, must be clear
+    /// let mut padded = bytes.clone();
+    /// padded[16 + 5] |= 1;
+    /// assert_eq!(State::from_bytes(&padded).err(), Some(Error::InvalidBytes));
+    ///
+    /// // Up to the top bit of each word, since each word holds 64 qubits
+    /// let mut padded = bytes.clone();
+    /// padded[16 + 8 + 7] |= 0x80;
+    /// assert_eq!(State::from_bytes(&padded).err(), Some(Error::InvalidBytes));
+    ///
+    /// let narrow = state.repack::<u32>();
+    /// assert!(State::<u32>::packed_from_bytes(&narrow.to_bytes()).unwrap() == narrow);
+    /// assert_eq!(State::from_bytes(&narrow.to_bytes()).err(), Some(Error::InvalidBytes));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let words = self.xz.rows() * self.xz.words();
+        let mut bytes = Vec::with_capacity(16 + W::WIDTH / 8 * words + self.r.len());
+        bytes.extend_from_slice(&(self.n as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.words as u64).to_le_bytes());
+        for i in 0..self.xz.rows() {
+            for word in &self.xz[i] {
+                word.write_le(&mut bytes);
+            }
+        }
+        bytes.extend(self.r.iter().map(|&r| r as u8));
+        bytes
+    }
+
+    /// Decode a tableau written by [`State::to_bytes`] from a state packed into words of type `W`,
+    /// like [`State::from_bytes`].
+    pub fn packed_from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let header = |k: usize| {
+            let chunk = bytes.get(8 * k..8 * k + 8)?;
+            Some(u64::from_le_bytes(chunk.try_into().unwrap()))
+        };
+        let (Some(n), Some(row_words)) = (header(0), header(1)) else {
+            return Err(Error::InvalidBytes);
+        };
+        let n = usize::try_from(n).map_err(|_| Error::InvalidBytes)?;
+        if row_words != (n / W::WIDTH) as u64 + 1 {
+            return Err(Error::InvalidBytes);
+        }
+
+        // Check the length before allocating, so a corrupted `n` can't request a huge tableau.
+        // Each row takes more than one byte, which also keeps the arithmetic below from overflowing.
+        if n > bytes.len() {
+            return Err(Error::InvalidBytes);
+        }
+        let rows = 2 * n + 1;
+        let word_bytes = W::WIDTH / 8;
+        if bytes.len() != 16 + 2 * word_bytes * row_words as usize * rows + rows {
+            return Err(Error::InvalidBytes);
+        }
+
+        // Each x or z word holds `W::WIDTH` qubits, and the last pair only as many as are left
+        let last = (0..n % W::WIDTH).fold(W::ZERO, |mask, j| mask | W::bit(j));
+        let padding = |k: usize| {
+            if k / 2 < n / W::WIDTH {
+                W::ZERO
+            } else {
+                !last
+            }
+        };
+
+        let mut words = bytes[16..].chunks_exact(word_bytes).map(W::read_le);
+        let mut state = Self::packed(n);
+        for i in 0..rows {
+            for (k, (word, value)) in state.xz[i].iter_mut().zip(words.by_ref()).enumerate() {
+                if value & padding(k) != W::ZERO {
+                    return Err(Error::InvalidBytes);
+                }
+                *word = value;
+            }
+        }
+        for (r, &byte) in state.r.iter_mut().zip(&bytes[bytes.len() - rows..]) {
+            if byte > 3 {
+                return Err(Error::InvalidBytes);
+            }
+            *r = byte.into();
+        }
+        Ok(state)
+    }
+
+    /// Measure the multi-qubit Pauli operator `pauli`, collapsing this state into one of its eigenspaces.
+    ///
+    /// A `1` is the `-1` eigenvalue of `pauli`, including its sign, so measuring `+Z_b` is the same as [`State::measure`].
+    ///
+    /// Returns an error if `pauli` acts on a different number of qubits or has an imaginary phase.
+    ///
+    /// ```
+    /// use circus::{pauli::PauliString, Error, State};
+    ///
+    /// let mut bell = State::new(2);
+    /// bell.h(0);
+    /// bell.cx(0, 1);
+    ///
+    /// let zz: PauliString = "+ZZ".parse().unwrap();
+    /// let mut state = bell.clone();
+    /// let parity = state.measure_pauli(&zz).unwrap();
+    /// assert!(!parity.is_random() && parity.is_zero());
+    ///
+    /// let xx: PauliString = "-XX".parse().unwrap();
+    /// assert!(state.measure_pauli(&xx).unwrap().is_one());
+    ///
+    /// assert!(bell.clone().measure(0).is_random());
+    ///
+    /// let iz: PauliString = "+iZZ".parse().unwrap();
+    /// assert_eq!(state.measure_pauli(&iz).err(), Some(Error::NonHermitian));
+    /// let z: PauliString = "+Z".parse().unwrap();
+    /// assert_eq!(
+    ///     state.measure_pauli(&z).err(),
+    ///     Some(Error::DimensionMismatch { expected: 2, found: 1 })
+    /// );
+    /// ```
+    pub fn measure_pauli(&mut self, pauli: &PauliString) -> Result<Measurement, Error> {
+        self.check_dimension(pauli.len())?;
+        if pauli.phase() % 2 != 0 {
+            return Err(Error::NonHermitian);
+        }
+        let xz = pauli.words();
+        let pauli = PauliStringRef {
+            n: pauli.len(),
+            xz: &xz,
+            r: pauli.phase(),
+        };
+        Ok(self.measure_pauli_with(&pauli, Self::random_bit))
+    }
+
+    /// Returns `Some(true)` if `pauli` is in the stabilizer group, `Some(false)` if its negative is,
+    /// or `None` if neither is, in which case measuring it would be random.
+    ///
+    /// This generalizes checking for a deterministic measurement from `Z_b` to any Pauli.
+    /// An operator with an imaginary phase is never in the group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pauli` acts on a different number of qubits.
+    ///
+    /// ```
+    /// use circus::{pauli::PauliString, State};
+    ///
+    /// let mut bell = State::new(2);
+    /// bell.h(0);
+    /// bell.cx(0, 1);
+    ///
+    /// let stabilized = |p: &str| bell.is_stabilized_by(&p.parse::<PauliString>().unwrap());
+    /// assert_eq!(stabilized("+ZZ"), Some(true));
+    /// assert_eq!(stabilized("-ZZ"), Some(false));
+    /// assert_eq!(stabilized("-YY"), Some(true));
+    /// assert_eq!(stabilized("+XI"), None);
+    /// assert_eq!(stabilized("+iZZ"), None);
+    /// ```
+    pub fn is_stabilized_by(&self, pauli: &PauliString) -> Option<bool> {
+        let n = self.n;
+        assert_eq!(
+            pauli.len(),
+            n,
+            "Pauli operator and state must have the same number of qubits"
+        );
+        let xz = pauli.words();
+        if (n..2 * n).any(|i| self.anticommutes(i, &xz)) {
+            return None;
+        }
+
+        // A commuting Pauli is the product of the stabilizers paired with the destabilizers it anticommutes with
+        let mut product = vec![W::ZERO; xz.len()];
+        let mut r = 0;
+        for i in (0..n).filter(|&i| self.anticommutes(i, &xz)) {
+            let row = &self.xz[i + n];
+            r = (phase_exponent(&product, row) + r + self.r[i + n]).rem_euclid(4);
+            xor_assign(&mut product, row);
+        }
+        match (r - pauli.phase()).rem_euclid(4) {
+            0 => Some(true),
+            2 => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Indices of the stabilizer generators that anticommute with `error`, in the order of [`State::stabilizers`].
+    ///
+    /// These are the generators whose signs applying `error` would flip, which is the syndrome a decoder reads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `error` acts on a different number of qubits.
+    ///
+    /// ```
+    /// use circus::{pauli::PauliString, State};
+    ///
+    /// // Stabilized by Z0, Z0 Z1 and Z1 Z2
+    /// let mut code = State::new(3);
+    /// code.cx(0, 1);
+    /// code.cx(1, 2);
+    ///
+    /// let error: PauliString = "+IXI".parse().unwrap();
+    /// assert_eq!(code.syndrome(&error), [1, 2]);
+    ///
+    /// let stabilizers = code.stabilizers();
+    /// assert_eq!(stabilizers[1].to_string(), "+ZZI");
+    /// assert_eq!(stabilizers[2].to_string(), "+IZZ");
+    ///
+    /// assert!(code.syndrome(&"+ZZZ".parse().unwrap()).is_empty());
+    /// ```
+    pub fn syndrome(&self, error: &PauliString) -> Vec<usize> {
+        let n = self.n;
+        assert_eq!(
+            error.len(),
+            n,
+            "Pauli operator and state must have the same number of qubits"
+        );
+        let xz = error.words();
+        (0..n).filter(|&i| self.anticommutes(i + n, &xz)).collect()
+    }
+
+    /// Apply a controlled-controlled-Z to `a`, `b` and `c` with the 7-T construction of [`clifford_t::ccz`],
+    /// injecting each of its T and T† gates from one of `magic_ancillas`, and return the number of injections.
+    ///
+    /// A stabilizer state can't hold the magic state `T|+>`, so the injections are accounted for rather than simulated:
+    /// each ancilla used, in the order given, is consumed by measuring it as the injection gadget does,
+    /// and any left over are untouched. The CCZ itself is applied exactly, which is possible when some product
+    /// of `Z_a`, `Z_b` and `Z_c` is in the stabilizer group, as for a computational basis state or a GHZ state.
+    /// One parity of the three qubits is then fixed, and CCZ acts on the state like a CZ or the identity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`State::try_ccz_via_t`] would return an error.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// // CCZ flips the sign of |111> and no other basis state, which shows up
+    /// // as a relative phase in (|000> + |x>) / √2
+    /// for x in 1..8 {
+    ///     let mut state = State::new(10);
+    ///     let mut qubits = (0..3).filter(|q| x >> q & 1 == 1);
+    ///     let first = qubits.next().unwrap();
+    ///     state.h(first);
+    ///     qubits.for_each(|q| state.cx(first, q));
+    ///
+    ///     let before = state.to_statevector().unwrap();
+    ///     assert_eq!(state.ccz_via_t(0, 1, 2, &[3, 4, 5, 6, 7, 8, 9]), 7);
+    ///     let after = state.to_statevector().unwrap();
+    ///
+    ///     let sign = if x == 0b111 { -1. } else { 1. };
+    ///     let ratio = |v: &[_]| v[x] / v[0];
+    ///     assert!((ratio(&after) - sign * ratio(&before)).norm() < 1e-12);
+    /// }
+    ///
+    /// // On a basis state CCZ only changes the global phase
+    /// for bits in 0..8 {
+    ///     let input: Vec<_> = (0..3).map(|q| bits >> q & 1 == 1).collect();
+    ///     let mut state = State::from_bits(&[&input[..], &[false; 7]].concat());
+    ///     assert_eq!(state.ccz_via_t(0, 1, 2, &[3, 4, 5, 6, 7, 8, 9]), 7);
+    ///     let outcomes: Vec<_> = (0..3).map(|q| state.measure(q).is_one()).collect();
+    ///     assert_eq!(outcomes, input);
+    /// }
+    /// ```
+    pub fn ccz_via_t(&mut self, a: usize, b: usize, c: usize, magic_ancillas: &[usize]) -> usize {
+        match self.try_ccz_via_t(a, b, c, magic_ancillas) {
+            Ok(injections) => injections,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Apply a controlled-controlled-Z like [`State::ccz_via_t`], or return an error without changing this state.
+    ///
+    /// This returns [`Error::QubitOutOfRange`] or [`Error::RepeatedQubit`] if `a`, `b`, `c` and the ancillas used
+    /// aren't distinct qubits of this state, [`Error::NotEnoughAncillas`] if there are fewer ancillas
+    /// than T gates, or [`Error::NonStabilizer`] if the CCZ would leave the stabilizer states.
+    ///
+    /// ```
+    /// use circus::{Error, State};
+    ///
+    /// let mut state = State::new(10);
+    /// state.h_range(0..3);
+    /// let ancillas = [3, 4, 5, 6, 7, 8, 9];
+    ///
+    /// assert_eq!(state.try_ccz_via_t(0, 1, 2, &ancillas), Err(Error::NonStabilizer));
+    /// assert_eq!(
+    ///     state.try_ccz_via_t(0, 1, 2, &ancillas[..6]),
+    ///     Err(Error::NotEnoughAncillas { needed: 7, found: 6 })
+    /// );
+    /// assert_eq!(
+    ///     state.try_ccz_via_t(0, 1, 2, &[2, 4, 5, 6, 7, 8, 9]),
+    ///     Err(Error::RepeatedQubit { qubit: 2 })
+    /// );
+    ///
+    /// // Measuring one of the qubits makes CCZ a CZ or the identity
+    /// state.measure(2);
+    /// assert_eq!(state.try_ccz_via_t(0, 1, 2, &ancillas), Ok(7));
+    /// ```
+    pub fn try_ccz_via_t(
+        &mut self,
+        a: usize,
+        b: usize,
+        c: usize,
+        magic_ancillas: &[usize],
+    ) -> Result<usize, Error> {
+        let injections = clifford_t::t_count(&clifford_t::ccz(a, b, c));
+        let Some(ancillas) = magic_ancillas.get(..injections) else {
+            return Err(Error::NotEnoughAncillas {
+                needed: injections,
+                found: magic_ancillas.len(),
+            });
+        };
+        self.check_distinct(&[&[a, b, c], ancillas].concat())?;
+
+        // Find a fixed parity of a subset of the qubits, and substitute it into the product abc
+        let qubits = [a, b, c];
+        let parity = (1..8usize).find_map(|subset| {
+            let mut z = PauliString::new(self.n);
+            for (k, &q) in qubits.iter().enumerate() {
+                if subset >> k & 1 == 1 {
+                    z.set(q, Pauli::Z);
+                }
+            }
+            let sign = self.is_stabilized_by(&z)?;
+            Some((subset, !sign))
+        });
+        let cz = match parity.ok_or(Error::NonStabilizer)? {
+            // With one qubit fixed, abc is the product of the other two, or zero
+            (subset @ (0b001 | 0b010 | 0b100), true) => {
+                let others: Vec<_> = (0..3).filter(|k| subset >> k & 1 == 0).collect();
+                Some((qubits[others[0]], qubits[others[1]]))
+            }
+            // With two qubits equal, abc is the product of one of them and the other qubit,
+            // and with them different it is zero
+            (subset @ (0b011 | 0b101 | 0b110), false) => {
+                let k = subset.trailing_zeros() as usize;
+                let other = (0..3).find(|k| subset >> k & 1 == 0).unwrap();
+                Some((qubits[k], qubits[other]))
+            }
+            // c = 1 + a + b makes abc = ab, and c = a + b makes it zero
+            (0b111, true) => Some((a, b)),
+            _ => None,
+        };
+        if let Some((p, q)) = cz {
+            self.cz(p, q);
+        }
+
+        for &ancilla in ancillas {
+            self.measure(ancilla);
+        }
+        Ok(injections)
+    }
+
+    /// Overlap `|<φ|ψ>|` between this state `ψ` and `other`.
+    ///
+    /// For stabilizer states this is either zero or `2^(-k/2)` for some `k`.
+    /// It is found by projecting a copy of this state onto each stabilizer generator of `other`.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let zero = State::new(1);
+    /// let mut plus = State::new(1);
+    /// plus.h(0);
+    ///
+    /// assert_eq!(zero.overlap(&plus), Ok(0.5f64.sqrt()));
+    /// ```
+    pub fn overlap(&self, other: &Self) -> Result<f64, Error> {
+        self.fidelity(other).map(f64::sqrt)
+    }
+
+    /// Fidelity `|<φ|ψ>|²` between this state `ψ` and `other`.
+    ///
+    /// For pure states this is the square of [`State::overlap`].
+    /// Returns an error if the states have different numbers of qubits.
+    ///
+    /// ```
+    /// use circus::{Error, State};
+    ///
+    /// let mut bell = State::new(2);
+    /// bell.h(0);
+    /// bell.cx(0, 1);
+    /// assert_eq!(bell.fidelity(&bell), Ok(1.));
+    ///
+    /// let mut orthogonal = bell.clone();
+    /// orthogonal.z(0);
+    /// assert_eq!(bell.fidelity(&orthogonal), Ok(0.));
+    ///
+    /// assert_eq!(bell.fidelity(&State::new(2)), Ok(0.5));
+    /// assert_eq!(
+    ///     bell.fidelity(&State::new(3)),
+    ///     Err(Error::DimensionMismatch { expected: 2, found: 3 })
+    /// );
+    /// ```
+    pub fn fidelity(&self, other: &Self) -> Result<f64, Error> {
+        self.check_dimension(other.n)?;
+        let mut state = self.clone();
+        let mut fidelity = 1.;
+        for stabilizer in other.iter_stabilizers() {
+            let measurement = state.measure_pauli_with(&stabilizer, |_| false);
+            if measurement.is_random() {
+                fidelity *= 0.5;
+            } else if measurement.is_one() {
+                return Ok(0.);
+            }
+        }
+        Ok(fidelity)
+    }
+
+    /// Print the tableau like [`Display`](fmt::Display), but with only the non-identity Paulis of each row
+    /// and their qubits, which is easier to read for many qubits.
+    ///
+    /// The destabilizers come first, one per line, then a `--` line and the stabilizers.
+    ///
+    /// ```
+    /// use circus::State;
+    ///
+    /// let mut bell = State::new(2);
+    /// bell.h(0);
+    /// bell.cx(0, 1);
+    /// assert_eq!(bell.display_sparse(), "+Z0\n+X1\n--\n+X0 X1\n+Z0 Z1\n");
+    ///
+    /// let mut state = State::new(100);
+    /// state.x(99);
+    /// assert!(state.display_sparse().ends_with("\n-Z99\n"));
+    /// ```
+    pub fn display_sparse(&self) -> String {
+        let mut s = String::new();
+        for i in 0..2 * self.n {
+            if i == self.n {
+                s.push_str("--\n");
+            }
+            self.row(i).write_sparse(&mut s).unwrap();
+            s.push('\n');
+        }
+        s
+    }
+
+    pub(crate) fn row(&self, i: usize) -> PauliStringRef<'_, W> {
+        PauliStringRef {
+            n: self.n,
+            xz: &self.xz[i],
+            r: self.r[i],
+        }
+    }
+
+    /// Measure the Pauli operator `pauli`, calling `bit` for the outcome if it is random.
+    ///
+    /// This generalizes [`State::collapse_to`] and [`State::deterministic_outcome`] to any Pauli,
+    /// where a `1` is the `-1` eigenvalue of `pauli` including its sign.
+    fn measure_pauli_with(
+        &mut self,
+        pauli: &PauliStringRef<'_, W>,
+        bit: impl FnOnce(&mut Self) -> bool,
+    ) -> Measurement {
+        let n = self.n;
+        assert_eq!(
+            pauli.n, n,
+            "Pauli operator and state must have the same number of qubits"
+        );
+
+        if let Some(p) = (0..n).find(|&p| self.anticommutes(p + n, pauli.xz)) {
+            let bit = bit(self);
+            self.rowcopy(p, p + n);
+            self.xz[p + n].copy_from_slice(pauli.xz);
+            self.r[p + n] = (pauli.r + 2 * bit as i32) % 4;
+            for i in 0..2 * n {
+                if i != p && self.anticommutes(i, pauli.xz) {
+                    self.rowmult(i, p);
+                }
+            }
+            Measurement::random(bit)
+        } else {
+            self.xz[2 * n].fill(W::ZERO);
+            self.r[2 * n] = 0;
+            for i in 0..n {
+                if self.anticommutes(i, pauli.xz) {
+                    self.rowmult(2 * n, i + n);
+                }
+            }
+            Measurement::fixed(self.r[2 * n] != pauli.r)
         }
     }
 }
 
-impl fmt::Display for State {
+/// The empty state on zero qubits, the same as `State::packed(0)`.
+///
+/// It has a single basis state with amplitude 1.
+///
+/// ```
+/// use circus::State;
+///
+/// let mut state: State = State::default();
+/// assert_eq!(state.num_qubits(), 0);
+/// assert_eq!(state.to_string(), "\n");
+/// assert_eq!(state.ket().unwrap(), " +|>\n");
+/// assert_eq!(state.rank(), 0);
+/// assert!(state.measure_all().is_empty());
+/// assert_eq!(state.to_statevector().unwrap().len(), 1);
+/// assert_eq!(state.fidelity(&State::new(0)), Ok(1.));
+/// ```
+impl<W: Word> Default for State<W> {
+    fn default() -> Self {
+        Self::packed(0)
+    }
+}
+
+/// Prints the dimensions of the tableau, leaving its contents to [`Display`](fmt::Display).
+///
+/// ```
+/// use circus::State;
+///
+/// let state = State::new(1000);
+/// let debug = format!("{state:?}");
+/// assert_eq!(debug, "State { n: 1000, words: 16, rows: 2001 }");
+/// ```
+impl<W: Word> fmt::Debug for State<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("State")
+            .field("n", &self.n)
+            .field("words", &self.words)
+            .field("rows", &self.xz.rows())
+            .finish()
+    }
+}
+
+impl<W: Word> fmt::Display for State<W> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for i in 0..2 * self.n {
             if i == self.n {
@@ -368,19 +3592,14 @@ impl fmt::Display for State {
                 f.write_str("\n+")?;
             }
             for j in 0..self.n {
-                let j5 = j >> 5;
-                let pw = PW[j & 31];
-                if (!(self.x[i][j5] & pw > 0)) && (!(self.z[i][j5] & pw > 0)) {
-                    f.write_str("I")?;
-                }
-                if (self.x[i][j5] & pw > 0) && (!(self.z[i][j5] & pw > 0)) {
-                    f.write_str("X")?;
-                }
-                if (self.x[i][j5] & pw > 0) && (self.z[i][j5] & pw > 0) {
-                    f.write_str("Y")?;
-                }
-                if (!(self.x[i][j5] & pw) > 0) && (self.z[i][j5] & pw > 0) {
-                    f.write_str("Z")?;
+                let (j5, pw) = W::locate(j);
+                let x = self.x_word(i, j5) & pw != W::ZERO;
+                let z = self.z_word(i, j5) & pw != W::ZERO;
+                match (x, z) {
+                    (false, false) => f.write_str("I")?,
+                    (true, false) => f.write_str("X")?,
+                    (true, true) => f.write_str("Y")?,
+                    (false, true) => f.write_str("Z")?,
                 }
             }
         }
@@ -388,12 +3607,156 @@ impl fmt::Display for State {
     }
 }
 
-pub struct Measurements<'s, I> {
-    state: &'s mut State,
+/// Phase exponent `e` (a power of `i`) picked up when multiplying the interleaved Pauli rows
+/// `k` and `i`, excluding the rows' own phases.
+///
+/// Each qubit contributes `+1` for the products `XY`, `YZ` and `ZX` and `-1` for `XZ`, `YX` and `ZY`,
+/// so both cases are computed a word at a time as bit masks and counted.
+pub(crate) fn phase_exponent<W: Word>(i: &[W], k: &[W]) -> i32 {
+    let mut e = 0;
+
+    for (i, k) in i.chunks_exact(2).zip(k.chunks_exact(2)) {
+        let (xi, zi, xk, zk) = (i[0], i[1], k[0], k[1]);
+
+        // XY=iZ, YZ=iX, ZX=iY
+        let plus = (xk & !zk & xi & zi) | (xk & zk & !xi & zi) | (!xk & zk & xi & !zi);
+        // XZ=-iY, YX=-iZ, ZY=-iX
+        let minus = (xk & !zk & !xi & zi) | (xk & zk & xi & !zi) | (!xk & zk & xi & zi);
+
+        e += plus.count_ones() as i32 - minus.count_ones() as i32;
+    }
+
+    e
+}
+
+/// A copy of a tableau row, detached from the `State` it came from.
+struct Row<W> {
+    xz: Box<[W]>,
+    r: i32,
+}
+
+impl<W: Word> Row<W> {
+    /// Left-multiply `rows[i]` by `rows[k]`.
+    fn mult(rows: &mut [Self], i: usize, k: usize) {
+        let (row, other) = row_pair(rows, i, k);
+
+        let e = phase_exponent(&row.xz, &other.xz);
+        row.r = (e + row.r + other.r).rem_euclid(4);
+        xor_assign(&mut row.xz, &other.xz);
+    }
+}
+
+/// Compare the destabilizer and stabilizer rows of two tableaux, ignoring the scratch row.
+///
+/// Equal tableaux describe the same state, but the same state can also be described by different tableaux.
+impl<W: Word> PartialEq for State<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.n == other.n
+            && (0..2 * self.n).all(|i| self.r[i] == other.r[i] && self.xz[i] == other.xz[i])
+    }
+}
+
+impl<W: Word> Eq for State<W> {}
+
+/// Stabilizer generators of a state in reduced row echelon form,
+/// returned by [`State::canonical`] and [`MixedState::canonical`].
+///
+/// Equal states have equal canonical forms, so this can be hashed to deduplicate them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CanonicalState {
+    stabilizers: Vec<PauliString>,
+}
+
+impl CanonicalState {
+    /// The reduced stabilizer generators, ordered by their first qubit acted on.
+    pub fn stabilizers(&self) -> &[PauliString] {
+        &self.stabilizers
+    }
+}
+
+/// Measurements that share one Gaussian elimination of the stabilizer generators,
+/// returned by [`State::measurement_session`] for [`State::measure_commuting`].
+///
+/// The first deterministic measurement finds every deterministic Z outcome at once, so later ones
+/// are a lookup. A random measurement keeps the cache, since each cached outcome is a Z that commutes
+/// with it. The session borrows the state mutably, so no gate can change the generators while it lives.
+///
+/// This is crate-private because it is only faster than [`State::measure`] when most outcomes are
+/// deterministic, which [`State::measure_commuting`] documents.
+pub(crate) struct MeasurementSession<'s, W: Word = u64> {
+    state: &'s mut State<W>,
+    readout: Option<Vec<Option<bool>>>,
+}
+
+impl<W: Word> MeasurementSession<'_, W> {
+    /// Measure the `target` qubit, using the cached readout if its outcome is deterministic.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` isn't a qubit of the state.
+    pub(crate) fn measure(&mut self, target: usize) -> Measurement {
+        self.state.assert_qubit(target);
+        if self.readout.is_none() && self.state.random_pivot(target).is_some() {
+            return self.state.measure(target);
+        }
+
+        let readout = self.readout.get_or_insert_with(|| self.state.z_readout());
+        match readout[target] {
+            Some(bit) => Measurement::fixed(bit),
+            None => {
+                // Random when the readout was made, but an earlier random measurement may have fixed it
+                let m = self.state.measure(target);
+                readout[target] = Some(m.is_one());
+                m
+            }
+        }
+    }
+}
+
+pub struct Measurements<'s, I, W: Word = u64> {
+    state: &'s mut State<W>,
     iter: I,
+    record: Vec<Measurement>,
+    detectors: Vec<bool>,
+    /// Index of the next instruction in the circuit.
+    #[cfg(feature = "tracing")]
+    position: usize,
+}
+
+impl<I, W: Word> Measurements<'_, I, W> {
+    /// Every measurement made so far, in order.
+    pub fn record(&self) -> &[Measurement] {
+        &self.record
+    }
+
+    /// Parity of each [`Instruction::Detector`] reached so far, where `true` means the detector fired.
+    ///
+    /// ```
+    /// use circus::{Instruction, State};
+    ///
+    /// let circuit = [
+    ///     Instruction::h(0),
+    ///     Instruction::cx(0, 1),
+    ///     Instruction::measure(0),
+    ///     Instruction::measure(1),
+    ///     Instruction::Detector {
+    ///         measurement_indices: vec![0, 1],
+    ///     },
+    /// ];
+    ///
+    /// for _ in 0..10 {
+    ///     let mut state = State::new(2);
+    ///     let mut measurements = state.run(circuit.clone());
+    ///     measurements.by_ref().for_each(drop);
+    ///     assert_eq!(measurements.detectors(), [false]);
+    /// }
+    /// ```
+    pub fn detectors(&self) -> &[bool] {
+        &self.detectors
+    }
 }
 
-impl<I> Iterator for Measurements<'_, I>
+impl<I, W: Word> Iterator for Measurements<'_, I, W>
 where
     I: Iterator<Item = Instruction>,
 {
@@ -402,11 +3765,52 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             if let Some(instruction) = self.iter.next() {
+                #[cfg(feature = "tracing")]
+                let _span = {
+                    let kind = match instruction {
+                        Instruction::Gate(_) | Instruction::GateBroadcast(_) => "gate",
+                        Instruction::Measure { .. } => "measure",
+                        Instruction::Detector { .. } => "detector",
+                    };
+                    let span = tracing::trace_span!("instruction", index = self.position, kind);
+                    self.position += 1;
+                    span.entered()
+                };
+
                 match instruction {
                     Instruction::Gate(gate) => {
+                        gate.qubits().for_each(|q| self.state.assert_qubit(q));
                         gate.apply(self.state);
                     }
-                    Instruction::Measure { target } => break Some(self.state.measure(target)),
+                    Instruction::GateBroadcast(gates) => {
+                        for gate in &gates {
+                            gate.qubits().for_each(|q| self.state.assert_qubit(q));
+                            gate.apply(self.state);
+                        }
+                    }
+                    Instruction::Measure { target } => {
+                        let measurement = self.state.measure(target);
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(
+                            qubit = target,
+                            outcome = measurement.is_one(),
+                            random = measurement.is_random(),
+                            "measurement"
+                        );
+                        self.record.push(measurement);
+                        break Some(measurement);
+                    }
+                    Instruction::Detector {
+                        measurement_indices,
+                    } => {
+                        let parity = measurement_indices.iter().fold(false, |parity, &index| {
+                            let measurement = self.record.get(index).unwrap_or_else(|| {
+                                panic!("detector refers to measurement {index}, which hasn't been made")
+                            });
+                            parity ^ measurement.bit()
+                        });
+                        self.detectors.push(parity);
+                    }
                 }
             } else {
                 break None;
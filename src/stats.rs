@@ -0,0 +1,108 @@
+//! Resource estimates for circuits.
+
+use crate::{gate::Gates, Instruction};
+
+/// Number of each kind of gate and measurement in a circuit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GateCounts {
+    pub cnot: usize,
+    pub cz: usize,
+    pub hadamard: usize,
+    pub iswap: usize,
+    pub pauli_x: usize,
+    pub pauli_y: usize,
+    pub pauli_z: usize,
+    pub phase: usize,
+    pub phase_dagger: usize,
+    pub measure: usize,
+}
+
+impl GateCounts {
+    /// Total number of gates, excluding measurements.
+    pub fn gates(&self) -> usize {
+        self.cnot
+            + self.cz
+            + self.hadamard
+            + self.iswap
+            + self.pauli_x
+            + self.pauli_y
+            + self.pauli_z
+            + self.phase
+            + self.phase_dagger
+    }
+
+    fn add(&mut self, gate: &Gates) {
+        let count = match gate {
+            Gates::CNot(_) => &mut self.cnot,
+            Gates::CZ(_) => &mut self.cz,
+            Gates::Hadamard(_) => &mut self.hadamard,
+            Gates::ISwap(_) => &mut self.iswap,
+            Gates::PauliX(_) => &mut self.pauli_x,
+            Gates::PauliY(_) => &mut self.pauli_y,
+            Gates::PauliZ(_) => &mut self.pauli_z,
+            Gates::Phase(_) => &mut self.phase,
+            Gates::PhaseDagger(_) => &mut self.phase_dagger,
+        };
+        *count += 1;
+    }
+}
+
+/// Count the gates and measurements in a circuit.
+///
+/// ```
+/// use circus::{prep, stats, Instruction};
+///
+/// let mut circuit = prep::ghz(4);
+/// circuit.extend((0..4).map(|target| Instruction::Measure { target }));
+///
+/// let counts = stats::gate_counts(&circuit);
+/// assert_eq!(counts.hadamard, 1);
+/// assert_eq!(counts.cnot, 3);
+/// assert_eq!(counts.measure, 4);
+/// assert_eq!(counts.gates(), 4);
+/// assert_eq!(stats::depth(&circuit), 5);
+/// ```
+pub fn gate_counts(instructions: &[Instruction]) -> GateCounts {
+    let mut counts = GateCounts::default();
+    for instruction in instructions {
+        match instruction {
+            Instruction::Gate(gate) => counts.add(gate),
+            Instruction::GateBroadcast(gates) => gates.iter().for_each(|gate| counts.add(gate)),
+            Instruction::Measure { .. } => counts.measure += 1,
+            Instruction::Detector { .. } => {}
+        }
+    }
+    counts
+}
+
+/// Estimate the depth of a circuit, scheduling each gate or measurement
+/// as soon as all of its qubits are free.
+pub fn depth(instructions: &[Instruction]) -> usize {
+    let mut layers: Vec<usize> = Vec::new();
+    let mut schedule = |qubits: &[usize]| {
+        if let Some(&max) = qubits.iter().max() {
+            if layers.len() <= max {
+                layers.resize(max + 1, 0);
+            }
+        }
+        let layer = qubits.iter().map(|&q| layers[q]).max().unwrap_or(0) + 1;
+        for &q in qubits {
+            layers[q] = layer;
+        }
+    };
+
+    for instruction in instructions {
+        match instruction {
+            Instruction::Gate(gate) => schedule(&gate.qubits().collect::<Vec<_>>()),
+            Instruction::GateBroadcast(gates) => {
+                for gate in gates {
+                    schedule(&gate.qubits().collect::<Vec<_>>());
+                }
+            }
+            Instruction::Measure { target } => schedule(&[*target]),
+            Instruction::Detector { .. } => {}
+        }
+    }
+
+    layers.into_iter().max().unwrap_or(0)
+}
@@ -0,0 +1,300 @@
+//! Reading circuits in the [Stim](https://github.com/quantumlib/Stim) text format.
+//!
+//! Supported instructions are `H`, `S`, `S_DAG`, `X`, `Y`, `Z`, `CX` (or `CNOT`/`ZCX`), `CZ` (or `ZCZ`),
+//! `ISWAP`, `M` (or `MZ`) and `DETECTOR`. `TICK` and comments are skipped.
+
+use crate::{
+    gate::{
+        CNotGate, CZGate, Gates, HadamardGate, ISwapGate, PauliXGate, PauliYGate, PauliZGate,
+        PhaseDaggerGate, PhaseGate,
+    },
+    Instruction,
+};
+use core::fmt;
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+/// Errors from reading a Stim circuit.
+#[derive(Debug)]
+pub enum StimError {
+    /// The circuit could not be read.
+    Io(io::Error),
+
+    /// An instruction isn't supported.
+    UnknownInstruction {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Name of the instruction.
+        name: String,
+    },
+
+    /// A target isn't a qubit index.
+    InvalidTarget {
+        /// Line number, starting at 1.
+        line: usize,
+        /// The target as written.
+        target: String,
+    },
+
+    /// A two-qubit gate was given an odd number of targets.
+    UnpairedTarget {
+        /// Line number, starting at 1.
+        line: usize,
+    },
+}
+
+impl fmt::Display for StimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to read circuit: {error}"),
+            Self::UnknownInstruction { line, name } => {
+                write!(f, "line {line}: unknown instruction `{name}`")
+            }
+            Self::InvalidTarget { line, target } => {
+                write!(f, "line {line}: invalid target `{target}`")
+            }
+            Self::UnpairedTarget { line } => {
+                write!(
+                    f,
+                    "line {line}: two-qubit gate has an odd number of targets"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for StimError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StimError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Open the Stim circuit at `path`, returning an iterator that parses its instructions one line at a time.
+///
+/// ```
+/// use circus::{stim, Instruction, State};
+///
+/// let path = std::env::temp_dir().join("circus_read_file.stim");
+/// std::fs::write(
+///     &path,
+///     "# Bell pair\nH 0\nTICK\nCX 0 1\nM 0 1\nDETECTOR(0, 0) rec[-1] rec[-2]\n",
+/// )
+/// .unwrap();
+///
+/// let circuit: Vec<_> = stim::read_file(&path)
+///     .unwrap()
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(
+///     circuit,
+///     [
+///         Instruction::h(0),
+///         Instruction::cx(0, 1),
+///         Instruction::measure(0),
+///         Instruction::measure(1),
+///         Instruction::Detector {
+///             measurement_indices: vec![1, 0]
+///         },
+///     ]
+/// );
+///
+/// let mut state = State::new(2);
+/// let mut measurements = state.run(stim::read_file(&path).unwrap().map(Result::unwrap));
+/// let m: Vec<_> = measurements.by_ref().collect();
+/// assert_eq!(m[0].is_one(), m[1].is_one());
+/// assert_eq!(measurements.detectors(), [false]);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn read_file(path: impl AsRef<Path>) -> Result<Instructions<BufReader<File>>, StimError> {
+    let file = File::open(path)?;
+    Ok(Instructions::new(BufReader::new(file)))
+}
+
+/// Iterator over the instructions of a Stim circuit, parsed one line at a time.
+pub struct Instructions<R> {
+    lines: io::Lines<R>,
+    line: usize,
+    measurements: usize,
+    pending: VecDeque<Instruction>,
+}
+
+impl<R: BufRead> Instructions<R> {
+    /// Parse the Stim circuit read from `reader`.
+    ///
+    /// ```
+    /// use circus::{
+    ///     gate::{Gates, HadamardGate},
+    ///     stim::{Instructions, StimError},
+    ///     Instruction,
+    /// };
+    ///
+    /// let mut instructions = Instructions::new("H 0 1\nCX 0\n".as_bytes());
+    /// assert_eq!(
+    ///     instructions.next().unwrap().unwrap(),
+    ///     Instruction::GateBroadcast(vec![
+    ///         Gates::Hadamard(HadamardGate { target: 0 }),
+    ///         Gates::Hadamard(HadamardGate { target: 1 }),
+    ///     ])
+    /// );
+    /// assert!(matches!(
+    ///     instructions.next(),
+    ///     Some(Err(StimError::UnpairedTarget { line: 2 }))
+    /// ));
+    /// ```
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line: 0,
+            measurements: 0,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Instructions<R> {
+    type Item = Result<Instruction, StimError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(instruction) = self.pending.pop_front() {
+                return Some(Ok(instruction));
+            }
+
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(error) => return Some(Err(error.into())),
+            };
+            self.line += 1;
+
+            if let Err(error) =
+                parse_line(&line, self.line, &mut self.measurements, &mut self.pending)
+            {
+                return Some(Err(error));
+            }
+        }
+    }
+}
+
+/// Parse a compact spec such as `"h0 s1 cx0,2 m3"`, where each word is a Stim instruction name
+/// followed by its comma-separated targets.
+///
+/// Errors report the position of the word, starting at 1, as their line.
+pub(crate) fn parse_spec(spec: &str) -> Result<Vec<Instruction>, StimError> {
+    let mut measurements = 0;
+    let mut instructions = VecDeque::new();
+    for (i, word) in spec.split_whitespace().enumerate() {
+        let (name, targets) = word
+            .find(|c: char| c.is_ascii_digit())
+            .map_or((word, ""), |i| word.split_at(i));
+        let line = format!("{name} {}", targets.replace(',', " "));
+        parse_line(&line, i + 1, &mut measurements, &mut instructions)?;
+    }
+    Ok(instructions.into())
+}
+
+/// Parse a single line of a Stim circuit, pushing its instructions to `instructions`
+/// and counting its measurements in `measurements`.
+fn parse_line(
+    line: &str,
+    number: usize,
+    measurements: &mut usize,
+    instructions: &mut VecDeque<Instruction>,
+) -> Result<(), StimError> {
+    let line = line.split('#').next().unwrap_or_default().trim();
+    let (name, rest) = line
+        .find(|c: char| c == '(' || c.is_whitespace())
+        .map_or((line, ""), |i| line.split_at(i));
+    if name.is_empty() {
+        return Ok(());
+    }
+
+    // Skip any parenthesized arguments, such as the coordinates of a detector
+    let rest = match rest.strip_prefix('(') {
+        Some(args) => args.split_once(')').map_or("", |(_, rest)| rest),
+        None => rest,
+    };
+    let invalid = |target: &str| StimError::InvalidTarget {
+        line: number,
+        target: target.to_owned(),
+    };
+
+    let name = name.to_ascii_uppercase();
+    if name == "DETECTOR" {
+        // Measurement record targets such as `rec[-1]` count back from the latest measurement
+        let measurement_indices = rest
+            .split_whitespace()
+            .map(|target| {
+                target
+                    .strip_prefix("rec[-")
+                    .and_then(|target| target.strip_suffix(']'))
+                    .and_then(|back| back.parse::<usize>().ok())
+                    .filter(|&back| back > 0 && back <= *measurements)
+                    .map(|back| *measurements - back)
+                    .ok_or_else(|| invalid(target))
+            })
+            .collect::<Result<_, _>>()?;
+        instructions.push_back(Instruction::Detector {
+            measurement_indices,
+        });
+        return Ok(());
+    }
+
+    let targets = rest
+        .split_whitespace()
+        .map(|target| target.parse().map_err(|_| invalid(target)))
+        .collect::<Result<Vec<usize>, _>>()?;
+
+    let single: Option<fn(usize) -> Gates> = match name.as_str() {
+        "TICK" => return Ok(()),
+        "M" | "MZ" => {
+            *measurements += targets.len();
+            instructions.extend(targets.into_iter().map(Instruction::measure));
+            return Ok(());
+        }
+        "H" => Some(|target| Gates::Hadamard(HadamardGate { target })),
+        "S" => Some(|target| Gates::Phase(PhaseGate { target })),
+        "S_DAG" => Some(|target| Gates::PhaseDagger(PhaseDaggerGate { target })),
+        "X" => Some(|target| Gates::PauliX(PauliXGate { target })),
+        "Y" => Some(|target| Gates::PauliY(PauliYGate { target })),
+        "Z" => Some(|target| Gates::PauliZ(PauliZGate { target })),
+        _ => None,
+    };
+
+    let gates: Vec<_> = if let Some(gate) = single {
+        targets.into_iter().map(gate).collect()
+    } else {
+        let pair: fn(usize, usize) -> Gates = match name.as_str() {
+            "CX" | "CNOT" | "ZCX" => |control, target| Gates::CNot(CNotGate { control, target }),
+            "CZ" | "ZCZ" => |a, b| Gates::CZ(CZGate { a, b }),
+            "ISWAP" => |a, b| Gates::ISwap(ISwapGate { a, b }),
+            _ => return Err(StimError::UnknownInstruction { line: number, name }),
+        };
+        if targets.len() % 2 != 0 {
+            return Err(StimError::UnpairedTarget { line: number });
+        }
+        targets
+            .chunks_exact(2)
+            .map(|pair_targets| pair(pair_targets[0], pair_targets[1]))
+            .collect()
+    };
+
+    match gates.len() {
+        0 => {}
+        1 => instructions.push_back(Instruction::Gate(gates[0])),
+        _ => instructions.push_back(Instruction::GateBroadcast(gates)),
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use crate::Instruction;
+use core::fmt;
+
+/// An instruction that can't be run, found by [`validate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// An instruction acts on a qubit that doesn't exist.
+    QubitOutOfRange {
+        /// Index of the instruction in the circuit.
+        index: usize,
+        /// The missing qubit.
+        qubit: usize,
+    },
+
+    /// A two-qubit gate acts on the same qubit twice, such as a CNOT with its control equal to its target.
+    RepeatedQubit {
+        /// Index of the instruction in the circuit.
+        index: usize,
+        /// The repeated qubit.
+        qubit: usize,
+    },
+
+    /// A detector refers to a measurement that hasn't been made yet.
+    MeasurementOutOfRange {
+        /// Index of the instruction in the circuit.
+        index: usize,
+        /// Index of the missing measurement.
+        measurement: usize,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::QubitOutOfRange { index, qubit } => {
+                write!(f, "instruction {index} acts on missing qubit {qubit}")
+            }
+            Self::RepeatedQubit { index, qubit } => {
+                write!(f, "instruction {index} acts on qubit {qubit} twice")
+            }
+            Self::MeasurementOutOfRange { index, measurement } => {
+                write!(
+                    f,
+                    "instruction {index} refers to measurement {measurement}, which hasn't been made"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Check that a circuit can be run on `n` qubits, returning the first offending instruction.
+///
+/// ```
+/// use circus::{validate, Instruction, ValidationError};
+///
+/// let circuit = [Instruction::h(0), Instruction::cx(0, 1), Instruction::measure(1)];
+/// assert_eq!(validate(2, &circuit), Ok(()));
+/// assert_eq!(
+///     validate(1, &circuit),
+///     Err(ValidationError::QubitOutOfRange { index: 1, qubit: 1 })
+/// );
+///
+/// let circuit = [Instruction::h(0), Instruction::cx(0, 0)];
+/// assert_eq!(
+///     validate(2, &circuit),
+///     Err(ValidationError::RepeatedQubit { index: 1, qubit: 0 })
+/// );
+/// ```
+pub fn validate(n: usize, instructions: &[Instruction]) -> Result<(), ValidationError> {
+    let mut measurements = 0;
+
+    for (index, instruction) in instructions.iter().enumerate() {
+        let gates = match instruction {
+            Instruction::Gate(gate) => core::slice::from_ref(gate),
+            Instruction::GateBroadcast(gates) => gates,
+            Instruction::Measure { target } => {
+                if *target >= n {
+                    return Err(ValidationError::QubitOutOfRange {
+                        index,
+                        qubit: *target,
+                    });
+                }
+                measurements += 1;
+                continue;
+            }
+            Instruction::Detector {
+                measurement_indices,
+            } => {
+                if let Some(&measurement) = measurement_indices
+                    .iter()
+                    .find(|&&measurement| measurement >= measurements)
+                {
+                    return Err(ValidationError::MeasurementOutOfRange { index, measurement });
+                }
+                continue;
+            }
+        };
+
+        for gate in gates {
+            let qubits: Vec<_> = gate.qubits().collect();
+            if let Some(&qubit) = qubits.iter().find(|&&q| q >= n) {
+                return Err(ValidationError::QubitOutOfRange { index, qubit });
+            }
+            if let [a, b] = qubits[..] {
+                if a == b {
+                    return Err(ValidationError::RepeatedQubit { index, qubit: a });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
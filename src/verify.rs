@@ -0,0 +1,202 @@
+//! Checking the tableau simulator against a dense state-vector simulator, for small circuits.
+
+use crate::{
+    gate::{CNotGate, CZGate, Gates, ISwapGate},
+    pauli::{Pauli, PauliString},
+    state::{phase_exponent, MAX_STATEVECTOR_QUBITS},
+    Instruction, State,
+};
+use num_complex::Complex64;
+
+/// Run `instructions` on `n` qubits through both [`State`] and a naive state-vector simulator,
+/// returning `true` if the final states agree up to a global phase.
+///
+/// Measurements take the tableau's outcome and project the state vector onto it,
+/// which must also have probability `1/2` if the tableau reports it as random or `1` otherwise.
+///
+/// # Panics
+///
+/// Panics if `n` is more than [`MAX_STATEVECTOR_QUBITS`].
+///
+/// ```
+/// use circus::{prep, verify, Instruction};
+///
+/// let mut circuit = prep::ghz(3);
+/// circuit.extend([Instruction::s_dag(2), Instruction::iswap(0, 2), Instruction::measure(1)]);
+/// assert!(verify::check_against_dense(3, &circuit));
+/// ```
+pub fn check_against_dense(n: usize, instructions: &[Instruction]) -> bool {
+    assert!(
+        n <= MAX_STATEVECTOR_QUBITS,
+        "too many qubits for a dense state"
+    );
+
+    let mut state = State::new(n);
+    let mut dense = Dense::new(n);
+    for instruction in instructions {
+        match instruction {
+            Instruction::Gate(gate) => {
+                state.apply_gate(gate);
+                dense.apply_gate(gate);
+            }
+            Instruction::GateBroadcast(gates) => {
+                for gate in gates {
+                    state.apply_gate(gate);
+                    dense.apply_gate(gate);
+                }
+            }
+            Instruction::Measure { target } => {
+                let m = state.measure(*target);
+                let p = dense.measure(*target, m.is_one());
+                let expected = if m.is_random() { 0.5 } else { 1. };
+                if (p - expected).abs() > 1e-9 {
+                    return false;
+                }
+            }
+            Instruction::Detector { .. } => {}
+        }
+    }
+
+    let amplitudes = state.to_statevector().unwrap();
+
+    // Find the global phase from the largest amplitude, then compare every amplitude under it
+    let k = (0..dense.amplitudes.len())
+        .max_by(|&a, &b| {
+            dense.amplitudes[a]
+                .norm()
+                .total_cmp(&dense.amplitudes[b].norm())
+        })
+        .unwrap();
+    let phase = amplitudes[k] / dense.amplitudes[k];
+    (phase.norm() - 1.).abs() < 1e-9
+        && amplitudes
+            .iter()
+            .zip(&dense.amplitudes)
+            .all(|(a, d)| (a - phase * d).norm() < 1e-9)
+}
+
+/// Multiply the Pauli strings `a · b` a word at a time, as the rows of a [`State`] are multiplied,
+/// and one qubit at a time from the products of single-qubit Paulis,
+/// returning `true` if both give the same phase.
+///
+/// # Panics
+///
+/// Panics if `a` and `b` act on different numbers of qubits.
+///
+/// ```
+/// use circus::{pauli::PauliString, verify};
+///
+/// let a: PauliString = "+XYZIY".parse().unwrap();
+/// let b: PauliString = "-YZXZY".parse().unwrap();
+/// assert!(verify::check_pauli_product(&a, &b));
+/// ```
+pub fn check_pauli_product(a: &PauliString, b: &PauliString) -> bool {
+    assert_eq!(a.len(), b.len(), "Pauli strings of different lengths");
+
+    let e = phase_exponent(b.as_ref().xz, a.as_ref().xz);
+    let word = (e + a.phase() + b.phase()).rem_euclid(4);
+
+    let mut bit = a.phase() + b.phase();
+    for j in 0..a.len() {
+        bit += match (a.get(j), b.get(j)) {
+            // XY=iZ, YZ=iX, ZX=iY
+            (Pauli::X, Pauli::Y) | (Pauli::Y, Pauli::Z) | (Pauli::Z, Pauli::X) => 1,
+            // XZ=-iY, YX=-iZ, ZY=-iX
+            (Pauli::X, Pauli::Z) | (Pauli::Y, Pauli::X) | (Pauli::Z, Pauli::Y) => -1,
+            _ => 0,
+        };
+    }
+    word == bit.rem_euclid(4)
+}
+
+/// The `2^n` amplitudes of a state, where bit `j` of each index is the value of qubit `j`.
+struct Dense {
+    amplitudes: Vec<Complex64>,
+}
+
+impl Dense {
+    /// The state `|0...0>`.
+    fn new(n: usize) -> Self {
+        let mut amplitudes = vec![Complex64::new(0., 0.); 1 << n];
+        amplitudes[0] = Complex64::new(1., 0.);
+        Self { amplitudes }
+    }
+
+    fn apply_gate(&mut self, gate: &Gates) {
+        let i = Complex64::i();
+        match *gate {
+            Gates::Hadamard(gate) => {
+                let s = 0.5f64.sqrt();
+                self.pairs(gate.target, |a, b| ((a + b) * s, (a - b) * s));
+            }
+            Gates::Phase(gate) => self.pairs(gate.target, |a, b| (a, b * i)),
+            Gates::PhaseDagger(gate) => self.pairs(gate.target, |a, b| (a, -b * i)),
+            Gates::PauliX(gate) => self.pairs(gate.target, |a, b| (b, a)),
+            Gates::PauliY(gate) => self.pairs(gate.target, |a, b| (-b * i, a * i)),
+            Gates::PauliZ(gate) => self.pairs(gate.target, |a, b| (a, -b)),
+            Gates::CNot(CNotGate { control, target }) => {
+                self.pairs_where(target, control, |a, b| (b, a))
+            }
+            Gates::CZ(CZGate { a, b }) => self.pairs_where(b, a, |x, y| (x, -y)),
+            Gates::ISwap(ISwapGate { a, b }) => {
+                // |01> and |10> swap with a factor of i
+                for index in 0..self.amplitudes.len() {
+                    if index >> a & 1 == 1 && index >> b & 1 == 0 {
+                        let other = index ^ (1 << a) ^ (1 << b);
+                        let (x, y) = (self.amplitudes[index], self.amplitudes[other]);
+                        self.amplitudes[index] = y * i;
+                        self.amplitudes[other] = x * i;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Project `target` onto `bit`, returning the probability it had.
+    fn measure(&mut self, target: usize, bit: bool) -> f64 {
+        let p: f64 = self
+            .amplitudes
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| (index >> target & 1 == 1) == bit)
+            .map(|(_, a)| a.norm_sqr())
+            .sum();
+
+        let scale = p.sqrt().recip();
+        for (index, a) in self.amplitudes.iter_mut().enumerate() {
+            *a = if (index >> target & 1 == 1) == bit {
+                *a * scale
+            } else {
+                Complex64::new(0., 0.)
+            };
+        }
+        p
+    }
+
+    /// Map each pair of amplitudes for `target` being 0 and 1 through `f`.
+    fn pairs(&mut self, target: usize, f: impl Fn(Complex64, Complex64) -> (Complex64, Complex64)) {
+        for index in 0..self.amplitudes.len() {
+            if index >> target & 1 == 0 {
+                let other = index | 1 << target;
+                (self.amplitudes[index], self.amplitudes[other]) =
+                    f(self.amplitudes[index], self.amplitudes[other]);
+            }
+        }
+    }
+
+    /// Like [`Dense::pairs`], but only where `control` is 1.
+    fn pairs_where(
+        &mut self,
+        target: usize,
+        control: usize,
+        f: impl Fn(Complex64, Complex64) -> (Complex64, Complex64),
+    ) {
+        for index in 0..self.amplitudes.len() {
+            if index >> target & 1 == 0 && index >> control & 1 == 1 {
+                let other = index | 1 << target;
+                (self.amplitudes[index], self.amplitudes[other]) =
+                    f(self.amplitudes[index], self.amplitudes[other]);
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Words that the rows of a [`State`](crate::State)'s tableau are packed into.
+
+use core::{
+    fmt::Debug,
+    hash::Hash,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not},
+};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+}
+
+/// Unsigned integer holding [`Word::WIDTH`] qubits of a tableau row, one per bit.
+///
+/// Narrower words waste fewer bits on small states, and wider ones need fewer operations per row.
+/// This is implemented for `u32` and `u64`, which is the default.
+pub trait Word:
+    sealed::Sealed
+    + Copy
+    + Debug
+    + Default
+    + Eq
+    + Hash
+    + Send
+    + Sync
+    + 'static
+    + BitAnd<Output = Self>
+    + BitAndAssign
+    + BitOr<Output = Self>
+    + BitOrAssign
+    + BitXor<Output = Self>
+    + BitXorAssign
+    + Not<Output = Self>
+{
+    /// Number of qubits packed into each word.
+    const WIDTH: usize;
+
+    /// The word with no bits set.
+    const ZERO: Self;
+
+    /// The word with only the bit of qubit `j` set, which is bit `j % WIDTH`.
+    fn bit(j: usize) -> Self;
+
+    /// Number of bits set.
+    fn count_ones(self) -> u32;
+
+    /// The word with only the lowest bit set in this one, or zero if none are.
+    fn lowest_one(self) -> Self;
+
+    /// Index of the word holding qubit `j`, and the mask of its bit within that word.
+    fn locate(j: usize) -> (usize, Self) {
+        (j / Self::WIDTH, Self::bit(j))
+    }
+
+    /// Append the little-endian bytes of this word to `bytes`.
+    #[doc(hidden)]
+    fn write_le(self, bytes: &mut Vec<u8>);
+
+    /// Read a word from its `WIDTH / 8` little-endian `bytes`.
+    #[doc(hidden)]
+    fn read_le(bytes: &[u8]) -> Self;
+
+    /// XOR the words of `src` into `dst`.
+    #[doc(hidden)]
+    fn xor_slice(dst: &mut [Self], src: &[Self]) {
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d ^= s;
+        }
+    }
+}
+
+impl Word for u32 {
+    const WIDTH: usize = 32;
+    const ZERO: Self = 0;
+
+    fn bit(j: usize) -> Self {
+        1 << (j % 32)
+    }
+
+    fn count_ones(self) -> u32 {
+        u32::count_ones(self)
+    }
+
+    fn lowest_one(self) -> Self {
+        self & self.wrapping_neg()
+    }
+
+    fn write_le(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_le(bytes: &[u8]) -> Self {
+        u32::from_le_bytes(bytes.try_into().unwrap())
+    }
+}
+
+impl Word for u64 {
+    const WIDTH: usize = 64;
+    const ZERO: Self = 0;
+
+    fn bit(j: usize) -> Self {
+        1 << (j % 64)
+    }
+
+    fn count_ones(self) -> u32 {
+        u64::count_ones(self)
+    }
+
+    fn lowest_one(self) -> Self {
+        self & self.wrapping_neg()
+    }
+
+    fn write_le(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_le(bytes: &[u8]) -> Self {
+        u64::from_le_bytes(bytes.try_into().unwrap())
+    }
+
+    #[cfg(all(feature = "simd", circus_nightly))]
+    fn xor_slice(dst: &mut [Self], src: &[Self]) {
+        crate::bits::xor_u64x8(dst, src)
+    }
+}
@@ -0,0 +1,123 @@
+use circus::{Error, State};
+
+type TryGate = fn(&mut State, usize) -> Result<(), Error>;
+type TryPairGate = fn(&mut State, usize, usize) -> Result<(), Error>;
+
+#[test]
+#[should_panic(expected = "qubit 7 is out of range for a state of 3 qubits")]
+fn entanglement_entropy_panics_past_the_last_qubit() {
+    State::new(3).entanglement_entropy(&[0, 7]);
+}
+
+#[test]
+#[should_panic(expected = "qubit 5 is out of range for a state of 3 qubits")]
+fn are_entangled_panics_past_the_last_qubit() {
+    State::new(3).are_entangled(5, 5);
+}
+
+#[test]
+#[should_panic(expected = "qubit 5 is out of range for a state of 3 qubits")]
+fn cz_panics_past_the_last_qubit() {
+    State::new(3).cz(0, 5);
+}
+
+#[test]
+#[should_panic(expected = "qubit 5 is out of range for a state of 3 qubits")]
+fn iswap_panics_past_the_last_qubit() {
+    State::new(3).iswap(0, 5);
+}
+
+#[test]
+#[should_panic(expected = "qubit 70 is out of range for a state of 3 qubits")]
+fn p_panics_past_the_last_qubit() {
+    State::new(3).p(70);
+}
+
+#[test]
+#[should_panic(expected = "qubit 3 is out of range for a state of 3 qubits")]
+fn cx_fanout_panics_past_the_last_qubit() {
+    State::new(3).cx_fanout(0, &[1, 3]);
+}
+
+#[test]
+#[should_panic(expected = "qubit 4 is out of range for a state of 3 qubits")]
+fn cz_layer_panics_past_the_last_qubit() {
+    State::new(3).cz_layer(&[(0, 1), (2, 4)]);
+}
+
+#[test]
+#[should_panic(expected = "qubit 5 is out of range for a state of 3 qubits")]
+fn measure_with_panics_past_the_last_qubit() {
+    State::new(3).measure_with(5, &mut rand::thread_rng());
+}
+
+#[test]
+#[should_panic(expected = "qubit 5 is out of range for a state of 3 qubits")]
+fn prob_one_panics_past_the_last_qubit() {
+    State::new(3).prob_one(5);
+}
+
+#[test]
+fn single_qubit_entry_points_panic_past_the_last_qubit() {
+    let entry_points: [fn(&mut State); 11] = [
+        |s| s.s_dag(3),
+        |s| s.x(3),
+        |s| s.y(3),
+        |s| s.z(3),
+        |s| s.s_pow(3, 4),
+        |s| s.apply_error(3, circus::pauli::Pauli::I),
+        |s| {
+            s.peek(3);
+        },
+        |s| {
+            s.measure_deterministic_zero(3);
+        },
+        |s| s.collapse(3),
+        |s| s.apply_gate(&circus::Gates::PauliX(circus::PauliXGate { target: 3 })),
+        |s| s.apply_layer(&[circus::Gates::PauliZ(circus::PauliZGate { target: 3 })]),
+    ];
+    for entry_point in entry_points {
+        let result = std::panic::catch_unwind(|| entry_point(&mut State::new(3)));
+        let message = result.unwrap_err();
+        let message = message.downcast_ref::<String>().unwrap();
+        assert_eq!(message, "qubit 3 is out of range for a state of 3 qubits");
+    }
+}
+
+#[test]
+fn checked_gates_return_errors_and_leave_the_state_unchanged() {
+    let mut state = State::new(3);
+    state.h(0);
+    state.cx(0, 1);
+    let before = state.clone();
+
+    let single: [TryGate; 6] = [
+        State::try_h,
+        State::try_s,
+        State::try_s_dag,
+        State::try_x,
+        State::try_y,
+        State::try_z,
+    ];
+    for try_gate in single {
+        assert_eq!(
+            try_gate(&mut state, 3),
+            Err(Error::QubitOutOfRange { qubit: 3, n: 3 })
+        );
+    }
+
+    let pairs: [TryPairGate; 3] = [State::try_cx, State::try_cz, State::try_iswap];
+    for try_gate in pairs {
+        assert_eq!(
+            try_gate(&mut state, 0, 5),
+            Err(Error::QubitOutOfRange { qubit: 5, n: 3 })
+        );
+        assert_eq!(
+            try_gate(&mut state, 2, 2),
+            Err(Error::RepeatedQubit { qubit: 2 })
+        );
+    }
+
+    assert_eq!(state, before);
+    assert_eq!(State::from_bytes(&state.to_bytes()), Ok(before));
+}
@@ -0,0 +1,65 @@
+use circus::{Instruction, State};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// A random circuit on `n` qubits of H, S, CX and measurements.
+fn random_circuit(n: usize, len: usize, rng: &mut StdRng) -> Vec<Instruction> {
+    (0..len)
+        .map(|_| {
+            let a = rng.gen_range(0..n);
+            match rng.gen_range(0..4) {
+                0 => Instruction::h(a),
+                1 => Instruction::p(a),
+                2 => Instruction::measure(a),
+                _ => Instruction::cx(a, (a + rng.gen_range(1..n)) % n),
+            }
+        })
+        .collect()
+}
+
+/// The destabilizer and then stabilizer rows of `state`, as displayed.
+fn rows(state: &State) -> Vec<String> {
+    state
+        .to_string()
+        .lines()
+        .filter(|line| !line.is_empty() && !line.chars().all(|c| c == '-'))
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn simd_rows_match_scalar_rows() {
+    // A 12-qubit state's rows are 2 words, all XORed by the scalar remainder. On 600 qubits they are
+    // 20 words, so qubits from 300 fall in the second 8-lane chunk and qubits 506..518 straddle
+    // the last chunk and the remainder.
+    let n = 12;
+    let mut rng = StdRng::seed_from_u64(581);
+    for offset in [300, 506] {
+        for seed in 0..50 {
+            let circuit = random_circuit(n, 200, &mut rng);
+
+            let mut small = State::seeded(n, seed);
+            let mut large = State::seeded(600, seed);
+            let expected: Vec<_> = small.run(circuit.iter().cloned()).collect();
+            let shifted = circuit.iter().map(|i| i.map_qubits(|q| q + offset));
+            let found: Vec<_> = large.run(shifted).collect();
+            assert_eq!(expected, found);
+
+            // Untouched qubits are never multiplied into, so each row matches the small tableau's
+            let (small, large) = (rows(&small), rows(&large));
+            for (i, row) in small.iter().enumerate() {
+                let shifted = if i < n {
+                    i + offset
+                } else {
+                    i - n + 600 + offset
+                };
+                let (sign, paulis) = row.split_at(1);
+                let expected = format!(
+                    "{sign}{}{paulis}{}",
+                    "I".repeat(offset),
+                    "I".repeat(600 - offset - n)
+                );
+                assert_eq!(large[shifted], expected);
+            }
+        }
+    }
+}
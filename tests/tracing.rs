@@ -0,0 +1,78 @@
+use circus::{Instruction, State};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+/// Counts instruction spans of kind `gate` and events.
+#[derive(Default)]
+struct Counts {
+    spans: AtomicUsize,
+    gates: AtomicUsize,
+    events: AtomicUsize,
+}
+
+struct CountingSubscriber(Arc<Counts>);
+
+struct GateVisitor(bool);
+
+impl Visit for GateVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "kind" && value == "gate" {
+            self.0 = true;
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+impl Subscriber for CountingSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        let mut visitor = GateVisitor(false);
+        span.record(&mut visitor);
+        if visitor.0 {
+            self.0.gates.fetch_add(1, Ordering::SeqCst);
+        }
+        span::Id::from_u64(self.0.spans.fetch_add(1, Ordering::SeqCst) as u64 + 1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, _event: &Event<'_>) {
+        self.0.events.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[test]
+fn traces_gates_and_measurements() {
+    let counts = Arc::new(Counts::default());
+    let subscriber = CountingSubscriber(counts.clone());
+
+    tracing::subscriber::with_default(subscriber, || {
+        let mut state = State::new(2);
+        let circuit = [
+            Instruction::h(0),
+            Instruction::cx(0, 1),
+            Instruction::measure(1),
+        ];
+        assert_eq!(state.run(circuit).count(), 1);
+    });
+
+    assert_eq!(counts.spans.load(Ordering::SeqCst), 3);
+    assert_eq!(counts.gates.load(Ordering::SeqCst), 2);
+    assert_eq!(counts.events.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,84 @@
+use circus::{
+    pauli::{Pauli, PauliString},
+    verify, Instruction, State,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// A random circuit on `n` qubits using every gate, with occasional measurements.
+fn random_circuit(n: usize, len: usize, rng: &mut StdRng) -> Vec<Instruction> {
+    (0..len)
+        .map(|_| {
+            let a = rng.gen_range(0..n);
+            let b = (a + rng.gen_range(1..n.max(2))) % n;
+            match rng.gen_range(0..if n > 1 { 11 } else { 8 }) {
+                0 => Instruction::h(a),
+                1 => Instruction::p(a),
+                2 => Instruction::s_dag(a),
+                3 => Instruction::x(a),
+                4 => Instruction::y(a),
+                5 => Instruction::z(a),
+                6 | 7 => Instruction::measure(a),
+                8 => Instruction::cx(a, b),
+                9 => Instruction::cz(a, b),
+                _ => Instruction::iswap(a, b),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn random_circuits_match_dense() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for n in 1..=8 {
+        for _ in 0..50 {
+            let circuit = random_circuit(n, 10 * n, &mut rng);
+            assert!(
+                verify::check_against_dense(n, &circuit),
+                "mismatch on {n} qubits for {circuit:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn phases_match_across_word_boundary() {
+    // The same circuit on qubits 0..7 of a 7-qubit state, which the dense simulator checks,
+    // and on qubits 28..35 of a 35-qubit state, where it straddles the first and second words
+    let mut rng = StdRng::seed_from_u64(35);
+    for seed in 0..200 {
+        let mut circuit = random_circuit(7, 60, &mut rng);
+        circuit.extend((0..7).flat_map(|q| [Instruction::h(q), Instruction::measure(q)]));
+        assert!(verify::check_against_dense(7, &circuit));
+
+        let mut small = State::seeded(7, seed);
+        let mut large = State::seeded(35, seed);
+        let expected: Vec<_> = small.run(circuit.iter().cloned()).collect();
+        let shifted = circuit.iter().map(|i| i.map_qubits(|q| q + 28));
+        let found: Vec<_> = large.run(shifted).collect();
+        assert_eq!(expected, found, "mismatch for {circuit:?}");
+    }
+}
+
+#[test]
+fn row_product_phases_match_per_qubit_products() {
+    let mut rng = StdRng::seed_from_u64(580);
+    let paulis = [Pauli::I, Pauli::X, Pauli::Y, Pauli::Z];
+    let mut random_string = |n: usize| {
+        let mut p = PauliString::new(n);
+        for j in 0..n {
+            p.set(j, paulis[rng.gen_range(0..4)]);
+        }
+        p.set_phase(rng.gen_range(0..4));
+        p
+    };
+
+    for n in [1, 2, 31, 32, 33, 64, 65, 200] {
+        for _ in 0..200 {
+            let (a, b) = (random_string(n), random_string(n));
+            assert!(
+                verify::check_pauli_product(&a, &b),
+                "mismatch for {a} · {b}"
+            );
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use circus::{
+    clifford::Clifford,
+    gate::{CNotGate, Gates},
+    pauli::{Pauli, PauliString},
+    Measurement, State, Word,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// An operation on a state, drawn at random so that both word types see the same sequence.
+#[derive(Clone, Debug)]
+enum Op {
+    H(usize),
+    P(usize),
+    SDag(usize),
+    X(usize),
+    Y(usize),
+    Z(usize),
+    Cx(usize, usize),
+    Cz(usize, usize),
+    ISwap(usize, usize),
+    CxFanout(usize, Vec<usize>),
+    HRange(usize, usize),
+    XRange(usize, usize),
+    HAllQubits,
+    Measure(usize),
+}
+
+fn random_ops(n: usize, len: usize, rng: &mut StdRng) -> Vec<Op> {
+    (0..len)
+        .map(|_| {
+            let a = rng.gen_range(0..n);
+            let b = (a + rng.gen_range(1..n)) % n;
+            match rng.gen_range(0..14) {
+                0 => Op::H(a),
+                1 => Op::P(a),
+                2 => Op::SDag(a),
+                3 => Op::X(a),
+                4 => Op::Y(a),
+                5 => Op::Z(a),
+                6 => Op::Cx(a, b),
+                7 => Op::Cz(a, b),
+                8 => Op::ISwap(a, b),
+                9 => Op::CxFanout(a, (0..4).map(|_| rng.gen_range(0..n)).collect()),
+                10 => Op::HRange(a.min(b), a.max(b)),
+                11 => Op::XRange(a.min(b), a.max(b)),
+                12 => Op::HAllQubits,
+                _ => Op::Measure(a),
+            }
+        })
+        .collect()
+}
+
+fn run<W: Word>(state: &mut State<W>, ops: &[Op]) -> Vec<Measurement> {
+    let mut measurements = Vec::new();
+    for op in ops {
+        match *op {
+            Op::H(a) => state.h(a),
+            Op::P(a) => state.p(a),
+            Op::SDag(a) => state.s_dag(a),
+            Op::X(a) => state.x(a),
+            Op::Y(a) => state.y(a),
+            Op::Z(a) => state.z(a),
+            Op::Cx(a, b) => state.cx(a, b),
+            Op::Cz(a, b) => state.cz(a, b),
+            Op::ISwap(a, b) => state.iswap(a, b),
+            Op::CxFanout(a, ref targets) => state.cx_fanout(a, targets),
+            Op::HRange(a, b) => state.h_range(a..b),
+            Op::XRange(a, b) => state.x_range(a..b),
+            Op::HAllQubits => state.h_all_qubits(),
+            Op::Measure(a) => measurements.push(state.measure(a)),
+        }
+    }
+    measurements
+}
+
+#[test]
+fn u32_and_u64_words_behave_identically() {
+    // 70 qubits take three 32-bit words or two 64-bit words, so gates and row products
+    // cross a word boundary in one layout but not the other
+    let n = 70;
+    let mut rng = StdRng::seed_from_u64(632);
+    for seed in 0..20 {
+        let ops = random_ops(n, 400, &mut rng);
+
+        let mut wide = State::seeded(n, seed);
+        let mut narrow = State::seeded(n, seed).repack::<u32>();
+        assert_eq!(run(&mut wide, &ops), run(&mut narrow, &ops));
+        assert_eq!(wide.to_string(), narrow.to_string());
+        assert!(narrow.repack::<u64>() == wide);
+
+        assert_eq!(wide.marginals(), narrow.marginals());
+        assert_eq!(wide.rank(), narrow.rank());
+        let qubits: Vec<_> = (0..n).step_by(3).collect();
+        assert_eq!(
+            wide.entanglement_entropy(&qubits),
+            narrow.entanglement_entropy(&qubits)
+        );
+
+        let perm: Vec<_> = (0..n).map(|q| (q * 29 + 3) % n).collect();
+        wide.permute(&perm).unwrap();
+        narrow.permute(&perm).unwrap();
+        let (wide, narrow) = (wide.tensor(&wide), narrow.tensor(&narrow));
+        assert_eq!(wide.to_string(), narrow.to_string());
+    }
+}
+
+#[test]
+fn u32_and_u64_words_give_the_same_ket() {
+    let n = 40;
+    let mut rng = StdRng::seed_from_u64(1632);
+    for seed in 0..20 {
+        // Few enough Hadamards to keep the number of basis states printable
+        let mut ops = random_ops(n, 200, &mut rng);
+        ops.retain(|op| !matches!(op, Op::H(_) | Op::HRange(..) | Op::HAllQubits));
+        ops.extend((0..6).map(|q| Op::H(q * 7)));
+        ops.extend((0..n - 1).map(|q| Op::Cx(q, q + 1)));
+
+        let mut wide = State::seeded(n, seed);
+        let mut narrow = State::seeded(n, seed).repack::<u32>();
+        assert_eq!(run(&mut wide, &ops), run(&mut narrow, &ops));
+        assert_eq!(wide.ket(), narrow.ket());
+        assert_eq!(wide.ket_pretty(), narrow.ket_pretty());
+        assert_eq!(
+            wide.is_computational_basis(),
+            narrow.is_computational_basis()
+        );
+        assert_eq!(wide.measure_all(), narrow.measure_all());
+    }
+}
+
+#[test]
+fn u32_and_u64_words_agree_on_pauli_operators() {
+    let n = 70;
+    let mut rng = StdRng::seed_from_u64(2632);
+    for seed in 0..10 {
+        let ops = random_ops(n, 300, &mut rng);
+
+        let mut wide = State::seeded(n, seed);
+        let mut narrow = State::<u32>::packed_seeded(n, seed);
+        assert_eq!(run(&mut wide, &ops), run(&mut narrow, &ops));
+
+        assert_eq!(wide.stabilizers(), narrow.stabilizers());
+        assert!(wide.canonical() == narrow.canonical());
+        assert_eq!(wide.display_sparse(), narrow.display_sparse());
+        assert_eq!(wide.fidelity(&wide), narrow.fidelity(&narrow));
+
+        let mut other = State::seeded(n, seed + 100);
+        run(&mut other, &random_ops(n, 100, &mut rng));
+        assert_eq!(
+            wide.fidelity(&other),
+            narrow.fidelity(&other.repack::<u32>())
+        );
+
+        let paulis = [Pauli::I, Pauli::X, Pauli::Y, Pauli::Z];
+        for _ in 0..5 {
+            let mut pauli = PauliString::new(n);
+            for j in 0..n {
+                pauli.set(j, paulis[rng.gen_range(0..4)]);
+            }
+            assert_eq!(wide.syndrome(&pauli), narrow.syndrome(&pauli));
+            assert_eq!(
+                wide.is_stabilized_by(&pauli),
+                narrow.is_stabilized_by(&pauli)
+            );
+            assert_eq!(wide.measure_pauli(&pauli), narrow.measure_pauli(&pauli));
+        }
+        assert!(narrow.repack::<u64>() == wide);
+
+        let cx = CNotGate {
+            control: 0,
+            target: n - 1,
+        };
+        let clifford = Clifford::from_gates(n, &[Gates::CNot(cx)]);
+        clifford.apply_to(&mut wide).unwrap();
+        clifford.apply_to(&mut narrow).unwrap();
+        assert!(narrow.repack::<u64>() == wide);
+
+        let bytes = narrow.to_bytes();
+        assert!(State::<u32>::packed_from_bytes(&bytes).unwrap() == narrow);
+
+        let qubits: Vec<_> = (0..n).step_by(2).collect();
+        let (mut wide, mut narrow) = (wide.into_mixed(), narrow.into_mixed());
+        wide.trace_out(&qubits);
+        narrow.trace_out(&qubits);
+        assert_eq!(wide.rank(), narrow.rank());
+        assert!(wide.canonical() == narrow.canonical());
+        for q in 0..n {
+            assert_eq!(wide.measure(q), narrow.measure(q));
+        }
+        assert!(wide.canonical() == narrow.canonical());
+    }
+}
+
+#[test]
+fn u32_words_build_the_same_basis_states() {
+    let bits: Vec<_> = (0..40).map(|q| q % 3 == 1).collect();
+    let narrow = State::<u32>::packed_from_bits(&bits);
+    assert!(narrow.repack::<u64>() == State::from_bits(&bits));
+    assert!(State::<u32>::default().repack::<u64>() == State::default());
+}